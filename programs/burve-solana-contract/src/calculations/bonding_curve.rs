@@ -1,19 +1,147 @@
+use crate::math::*;
+
 #[derive(Debug, Clone)]
 pub struct Parameters {
-    pub a: u64,
-    pub b: u64,
+    pub a: u128,
+    pub b: u128,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CurveError {
+    // An intermediate or resulting amount does not fit its integer type
+    Overflow,
+    // Burning more tokens than the current supply, or going past the supply
+    // or reserve the curve can cover
+    InsufficientSupply,
+    // The trade is too small to produce anything
+    ZeroOutput,
+    // The curve cannot be evaluated with these parameters, e.g. a zero divisor
+    InvalidParameters,
+}
+
+// Curves work on amounts normalised to 18 decimals, so a whole token is always
+// WAD regardless of the token's own decimals.
+pub const MAX_DECIMALS: u8 = PRECISION as u8;
+
+// Decimals of the raising token and of the project token, used to convert
+// on-chain base units to and from the normalised amounts the curves expect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenDecimals {
+    pub raising: u8,
+    pub project: u8,
 }
 
+impl TokenDecimals {
+    pub fn new(raising: u8, project: u8) -> Result<Self, CurveError> {
+        if raising > MAX_DECIMALS || project > MAX_DECIMALS {
+            return Err(CurveError::InvalidParameters);
+        }
+        Ok(Self { raising, project })
+    }
+
+    pub fn raising_to_wad(&self, amount: u64) -> u128 {
+        to_wad(amount, self.raising)
+    }
+
+    pub fn project_to_wad(&self, amount: u64) -> u128 {
+        to_wad(amount, self.project)
+    }
+
+    pub fn raising_from_wad(&self, amount: u128, rounding: Rounding) -> Result<u64, CurveError> {
+        from_wad(amount, self.raising, rounding)
+    }
+
+    pub fn project_from_wad(&self, amount: u128, rounding: Rounding) -> Result<u64, CurveError> {
+        from_wad(amount, self.project, rounding)
+    }
+}
+
+fn to_wad(amount: u64, decimals: u8) -> u128 {
+    // decimals <= 18, so this cannot overflow
+    amount as u128 * 10u128.pow((MAX_DECIMALS - decimals) as u32)
+}
+
+fn from_wad(amount: u128, decimals: u8, rounding: Rounding) -> Result<u64, CurveError> {
+    let amount = mul_div(
+        amount,
+        1,
+        10u128.pow((MAX_DECIMALS - decimals) as u32),
+        rounding,
+    )
+    .ok_or(CurveError::Overflow)?;
+    u64::try_from(amount).map_err(|_| CurveError::Overflow)
+}
+
+// All amounts are normalised to 18 decimals, see `TokenDecimals`
 pub trait BondingCurve {
+    // Most curves take two `Parameters`, others bring their own shape
+    type Params;
+
     fn calculate_mint_amount_from_bonding_curve(
-        native_asset: u64,
-        current_supply: u64,
-        params: Parameters,
-    ) -> (u64, u64);
+        native_asset: u128,
+        current_supply: u128,
+        params: Self::Params,
+    ) -> Result<(u128, u128), CurveError>;
     fn calculate_burn_amount_from_bonding_curve(
-        token_amount: u64,
-        current_supply: u64,
-        params: Parameters,
-    ) -> (u64, u64);
-    fn price(current_supply: u64, params: Parameters) -> u64;
+        token_amount: u128,
+        current_supply: u128,
+        params: Self::Params,
+    ) -> Result<(u128, u128), CurveError>;
+    fn price(current_supply: u128, params: Self::Params) -> Result<u128, CurveError>;
+    // Raising amount needed to mint exactly `token_amount`, rounded up
+    fn cost_to_mint_exact(
+        token_amount: u128,
+        current_supply: u128,
+        params: Self::Params,
+    ) -> Result<(u128, u128), CurveError>;
+    // Tokens to burn to receive at least `raising_token_amount`, rounded up
+    fn tokens_to_burn_for_exact_out(
+        raising_token_amount: u128,
+        current_supply: u128,
+        params: Self::Params,
+    ) -> Result<(u128, u128), CurveError>;
+
+    // Rejects parameters a project cannot launch with: the price must be
+    // positive by the first whole token and representable up to `max_supply`
+    fn validate_parameters(max_supply: u128, params: Self::Params) -> Result<(), CurveError>
+    where
+        Self::Params: Clone,
+    {
+        let first_price = Self::price(WAD.min(max_supply), params.clone());
+        let max_price = Self::price(max_supply, params);
+        match (first_price, max_price) {
+            (Ok(first_price), Ok(_)) if first_price > 0 => Ok(()),
+            _ => Err(CurveError::InvalidParameters),
+        }
+    }
+
+    // Raising amount the vault holds at `current_supply`, i.e. what burning the
+    // whole supply returns, rounded down like every burn
+    fn reserve_at_supply(current_supply: u128, params: Self::Params) -> Result<u128, CurveError> {
+        if current_supply == 0 {
+            return Ok(0);
+        }
+        match Self::calculate_burn_amount_from_bonding_curve(current_supply, current_supply, params)
+        {
+            Ok((_, reserve)) => Ok(reserve),
+            // Too little minted for the reserve to register
+            Err(CurveError::ZeroOutput) => Ok(0),
+            Err(err) => Err(err),
+        }
+    }
+    // Fully diluted value of `current_supply` at the current price, rounded down
+    fn market_cap(current_supply: u128, params: Self::Params) -> Result<u128, CurveError> {
+        let price = Self::price(current_supply, params)?;
+        mul_div(current_supply, price, WAD, Rounding::Down).ok_or(CurveError::Overflow)
+    }
+    // Average price paid per whole token when minting `token_amount` at
+    // `current_supply`, rounded up
+    fn average_price(
+        token_amount: u128,
+        current_supply: u128,
+        params: Self::Params,
+    ) -> Result<u128, CurveError> {
+        let (_, cost) = Self::cost_to_mint_exact(token_amount, current_supply, params)?;
+        mul_div(cost, WAD, token_amount, Rounding::Up).ok_or(CurveError::Overflow)
+    }
 }
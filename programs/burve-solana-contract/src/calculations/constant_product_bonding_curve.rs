@@ -0,0 +1,160 @@
+// src/calculations/constant_product_bonding_curve.rs
+use crate::bonding_curve::*;
+use crate::math::*;
+
+pub struct ConstantProductMixedBondingSwap;
+
+// x * y = k over virtual reserves, in WAD:
+// X0 => virtual token reserve before anything is minted (parameters.a)
+// Y0 => virtual raising reserve before anything is paid in (parameters.b)
+// X(s) = X0 - s, Y(s) = X0 * Y0 / X(s)
+// price(s) = Y(s) / X(s)
+// reserve(s) = Y(s) - Y0, so at most X0 - 1 wei can ever be minted
+fn unpack_parameters(parameters: Parameters) -> Result<(u128, u128), CurveError> {
+    if parameters.a == 0 || parameters.b == 0 {
+        return Err(CurveError::InvalidParameters);
+    }
+    Ok((parameters.a, parameters.b))
+}
+
+// X(s), the virtual tokens still left on the curve
+fn token_reserve(token_current_supply: u128, x0: u128) -> Result<u128, CurveError> {
+    x0.checked_sub(token_current_supply)
+        .filter(|x| *x > 0)
+        .ok_or(CurveError::InsufficientSupply)
+}
+
+// Y(s) = X0 * Y0 / X(s)
+fn raising_reserve(
+    token_reserve: u128,
+    x0: u128,
+    y0: u128,
+    rounding: Rounding,
+) -> Result<u128, CurveError> {
+    mul_div(x0, y0, token_reserve, rounding).ok_or(CurveError::Overflow)
+}
+
+impl BondingCurve for ConstantProductMixedBondingSwap {
+    type Params = Parameters;
+
+    // Δx = X - k / (Y + Δy) = X * Δy / (Y + Δy)
+    fn calculate_mint_amount_from_bonding_curve(
+        raising_token_amount: u128,
+        token_current_supply: u128,
+        parameters: Parameters,
+    ) -> Result<(u128, u128), CurveError> {
+        let (x0, y0) = unpack_parameters(parameters)?;
+        let x = token_reserve(token_current_supply, x0)?;
+        let y = raising_reserve(x, x0, y0, Rounding::Up)?;
+        let token_amount = y
+            .checked_add(raising_token_amount)
+            .and_then(|new_y| mul_div(x, raising_token_amount, new_y, Rounding::Down))
+            .ok_or(CurveError::Overflow)?;
+        if token_amount == 0 {
+            return Err(CurveError::ZeroOutput);
+        }
+
+        Ok((token_amount, raising_token_amount))
+    }
+
+    // Δy = Y - k / (X + Δx) = Y0 * Δx / (X + Δx) * X0 / X
+    fn calculate_burn_amount_from_bonding_curve(
+        token_amount: u128,
+        token_current_supply: u128,
+        parameters: Parameters,
+    ) -> Result<(u128, u128), CurveError> {
+        let (x0, y0) = unpack_parameters(parameters)?;
+        if token_amount > token_current_supply {
+            return Err(CurveError::InsufficientSupply);
+        }
+        let x = token_reserve(token_current_supply, x0)?;
+        let raising_token_amount = mul_div(y0, token_amount, x + token_amount, Rounding::Down)
+            .and_then(|y| mul_div(y, x0, x, Rounding::Down))
+            .ok_or(CurveError::Overflow)?;
+        if raising_token_amount == 0 {
+            return Err(CurveError::ZeroOutput);
+        }
+
+        Ok((token_amount, raising_token_amount))
+    }
+
+    // price = Y / X
+    fn price(token_current_supply: u128, parameters: Parameters) -> Result<u128, CurveError> {
+        let (x0, y0) = unpack_parameters(parameters)?;
+        let x = token_reserve(token_current_supply, x0)?;
+        let y = raising_reserve(x, x0, y0, Rounding::Down)?;
+        mul_div(y, WAD, x, Rounding::Down).ok_or(CurveError::Overflow)
+    }
+
+    // Minting never reaches X0, so past it there is nothing left to price
+    fn validate_parameters(max_supply: u128, parameters: Parameters) -> Result<(), CurveError> {
+        let (x0, _) = unpack_parameters(parameters.clone())?;
+        let first_price = Self::price(0, parameters.clone());
+        let max_price = if max_supply < x0 {
+            Self::price(max_supply, parameters).map(|_| ())
+        } else {
+            Ok(())
+        };
+        match (first_price, max_price) {
+            (Ok(first_price), Ok(())) if first_price > 0 => Ok(()),
+            _ => Err(CurveError::InvalidParameters),
+        }
+    }
+
+    // Δy = k / (X - Δx) - Y = Y * Δx / (X - Δx)
+    fn cost_to_mint_exact(
+        token_amount: u128,
+        token_current_supply: u128,
+        parameters: Parameters,
+    ) -> Result<(u128, u128), CurveError> {
+        let (x0, y0) = unpack_parameters(parameters)?;
+        if token_amount == 0 {
+            return Err(CurveError::ZeroOutput);
+        }
+        let x = token_reserve(token_current_supply, x0)?;
+        // The last virtual token can never be bought
+        let new_x = x
+            .checked_sub(token_amount)
+            .filter(|new_x| *new_x > 0)
+            .ok_or(CurveError::InsufficientSupply)?;
+        // Priced off the rounded up Y the mint uses, plus a wei for the mint
+        // flooring the tokens
+        let y = raising_reserve(x, x0, y0, Rounding::Up)?;
+        let raising_token_amount = mul_div(y, token_amount, new_x, Rounding::Up)
+            .and_then(|y| y.checked_add(1))
+            .ok_or(CurveError::Overflow)?;
+
+        Ok((token_amount, raising_token_amount))
+    }
+
+    // Δx = k / (Y - Δy) - X = X * Δy / (Y - Δy)
+    fn tokens_to_burn_for_exact_out(
+        raising_token_amount: u128,
+        token_current_supply: u128,
+        parameters: Parameters,
+    ) -> Result<(u128, u128), CurveError> {
+        let (x0, y0) = unpack_parameters(parameters)?;
+        if raising_token_amount == 0 {
+            return Err(CurveError::ZeroOutput);
+        }
+        let x = token_reserve(token_current_supply, x0)?;
+        let y = raising_reserve(x, x0, y0, Rounding::Down)?;
+        // Burning floors twice, the first time before scaling by X0 / X, so
+        // quote for that much more
+        let target_amount = raising_token_amount
+            .checked_add(x0 / x + 2)
+            .ok_or(CurveError::Overflow)?;
+        // Y0 is virtual, only Y - Y0 can be paid out
+        let new_y = y
+            .checked_sub(target_amount)
+            .filter(|new_y| *new_y >= y0)
+            .ok_or(CurveError::InsufficientSupply)?;
+        let token_amount =
+            mul_div(x, target_amount, new_y, Rounding::Up).ok_or(CurveError::Overflow)?;
+        if token_amount > token_current_supply {
+            return Err(CurveError::InsufficientSupply);
+        }
+
+        Ok((token_amount, raising_token_amount))
+    }
+}
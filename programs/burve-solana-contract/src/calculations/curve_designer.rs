@@ -0,0 +1,297 @@
+// src/calculations/curve_designer.rs
+use crate::bonding_curve::*;
+use crate::curve_registry::*;
+use crate::math::*;
+use crate::power_bonding_curve::*;
+use crate::stepped_bonding_curve::*;
+use crate::{stepped_tiers, BondingCurveType, StepTier, MAX_STEPPED_TIERS};
+use anchor_lang::prelude::{borsh, AnchorDeserialize, AnchorSerialize};
+
+// Designed curves may miss the target raise by this fraction of it, from
+// truncating their parameters
+pub const TARGET_RAISE_TOLERANCE: u128 = 100_000;
+
+// Business targets for a launch, in WAD. Each curve kind fits the targets its
+// two or three parameters can express, see `design_curve`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LaunchTargets {
+    // Price of the first token
+    pub initial_price: u128,
+    // Price once `target_supply` tokens are minted
+    pub target_price: u128,
+    pub target_supply: u128,
+    // Reserve held once `target_supply` tokens are minted. The power curve is
+    // fitted to it, the other kinds only check they meet it.
+    pub target_raise: Option<u128>,
+    // The curve must still be usable at this supply
+    pub max_supply: u128,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CurveKind {
+    Linear,
+    Exponential,
+    Power,
+    ConstantProduct,
+    Stepped,
+    Sigmoid,
+}
+
+// Price of the next token and reserve held at a given supply
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CurveReport {
+    pub price: u128,
+    pub reserve: u128,
+}
+
+fn validate_targets(targets: &LaunchTargets) -> Result<(), CurveError> {
+    if targets.initial_price == 0
+        || targets.target_price <= targets.initial_price
+        || targets.target_supply == 0
+        || targets.target_raise == Some(0)
+        || targets.max_supply < targets.target_supply
+    {
+        return Err(CurveError::InvalidParameters);
+    }
+    Ok(())
+}
+
+// Linear: starts at the initial price and reaches the target price at the
+// target supply, k = (target_price - initial_price) / target_supply
+fn design_linear(targets: &LaunchTargets) -> Result<BondingCurveType, CurveError> {
+    let k = mul_div(
+        targets.target_price - targets.initial_price,
+        WAD,
+        targets.target_supply,
+        Rounding::Down,
+    )
+    .ok_or(CurveError::Overflow)?;
+    Ok(BondingCurveType::Linear {
+        a: k,
+        b: targets.initial_price,
+    })
+}
+
+// Exponential: a = initial_price, b = target_supply / ln(target_price / initial_price)
+fn design_exponential(targets: &LaunchTargets) -> Result<BondingCurveType, CurveError> {
+    let ln_growth = div_wad(targets.target_price, targets.initial_price, Rounding::Down)
+        .and_then(ln_wad)
+        .filter(|x| *x > 0)
+        .ok_or(CurveError::InvalidParameters)?;
+    let b = mul_div(
+        targets.target_supply,
+        WAD,
+        ln_growth as u128,
+        Rounding::Down,
+    )
+    .ok_or(CurveError::Overflow)?;
+    Ok(BondingCurveType::Exponential {
+        a: targets.initial_price,
+        b,
+    })
+}
+
+// Power: holds the target raise at the target price and supply,
+// r = target_raise / (target_supply * target_price), m = target_price / target_supply ^ (1 / r - 1)
+fn design_power(targets: &LaunchTargets) -> Result<BondingCurveType, CurveError> {
+    let market_cap = mul_div(
+        targets.target_supply,
+        targets.target_price,
+        WAD,
+        Rounding::Up,
+    )
+    .ok_or(CurveError::Overflow)?;
+    let target_raise = targets.target_raise.ok_or(CurveError::InvalidParameters)?;
+    let r = mul_div(target_raise, WAD, market_cap, Rounding::Down)
+        .filter(|r| (MIN_RESERVE_RATIO..=WAD).contains(r))
+        .ok_or(CurveError::InvalidParameters)?;
+    let exponent = div_wad(WAD, r, Rounding::Down).ok_or(CurveError::Overflow)? - WAD;
+    let ln_supply = ln_wad(targets.target_supply).ok_or(CurveError::Overflow)?;
+    let ln_price = ln_wad(targets.target_price).ok_or(CurveError::Overflow)?;
+    let ln_m = mul_div(ln_supply.unsigned_abs(), exponent, WAD, Rounding::Down)
+        .and_then(|x| i128::try_from(x).ok())
+        .map(|x| {
+            if ln_supply < 0 {
+                ln_price + x
+            } else {
+                ln_price - x
+            }
+        })
+        .ok_or(CurveError::Overflow)?;
+    let m = exp_wad(ln_m)
+        .filter(|m| *m > 0)
+        .ok_or(CurveError::InvalidParameters)?;
+    Ok(BondingCurveType::Power { a: m, b: r })
+}
+
+// Constant product: Y0 / X0 = initial_price and (X0 / (X0 - target_supply)) ^ 2
+// = target_price / initial_price, so X0 = target_supply * q / (q - 1) with
+// q = sqrt(target_price / initial_price)
+fn design_constant_product(targets: &LaunchTargets) -> Result<BondingCurveType, CurveError> {
+    let growth = div_wad(targets.target_price, targets.initial_price, Rounding::Down)
+        .ok_or(CurveError::Overflow)?;
+    let q = sqrt_wad(growth, Rounding::Down);
+    let virtual_token_reserve = q
+        .checked_sub(WAD)
+        .filter(|x| *x > 0)
+        .and_then(|x| mul_div(targets.target_supply, q, x, Rounding::Up))
+        .ok_or(CurveError::InvalidParameters)?;
+    // The curve can never mint its whole virtual reserve
+    if virtual_token_reserve <= targets.max_supply {
+        return Err(CurveError::InvalidParameters);
+    }
+    let virtual_raising_reserve = mul_div(
+        virtual_token_reserve,
+        targets.initial_price,
+        WAD,
+        Rounding::Down,
+    )
+    .ok_or(CurveError::Overflow)?;
+    Ok(BondingCurveType::ConstantProduct {
+        virtual_token_reserve,
+        virtual_raising_reserve,
+    })
+}
+
+// Stepped: MAX_STEPPED_TIERS tiers priced evenly from the initial price up to
+// the target price. The last tier sells at the target price from the target
+// supply up to the max supply, and the others split the target supply evenly.
+fn design_stepped(targets: &LaunchTargets) -> Result<BondingCurveType, CurveError> {
+    let tier_count = MAX_STEPPED_TIERS as u128;
+    // Without supply past the target supply, the last tier ends there too
+    let tiers_below_target = if targets.target_supply < targets.max_supply {
+        tier_count - 1
+    } else {
+        tier_count
+    };
+    let price_range = targets.target_price - targets.initial_price;
+    let tiers = (0..tier_count)
+        .map(|i| {
+            let supply_breakpoint = if i < tiers_below_target {
+                mul_div(
+                    targets.target_supply,
+                    i + 1,
+                    tiers_below_target,
+                    Rounding::Down,
+                )?
+            } else {
+                targets.max_supply
+            };
+            let price = mul_div(price_range, i, tier_count - 1, Rounding::Down)?
+                .checked_add(targets.initial_price)?;
+            Some(StepTier {
+                supply_breakpoint,
+                price,
+            })
+        })
+        .collect::<Option<Vec<_>>>()
+        .ok_or(CurveError::Overflow)?;
+    SteppedMixedBondingSwap::validate_tiers(&stepped_tiers(tiers.clone()))?;
+    Ok(BondingCurveType::Stepped { tiers })
+}
+
+// Sigmoid: the target supply is the midpoint, so the curve flattens out at
+// twice the target price, and the steepness puts the initial price at zero
+// supply, k = ln(max_price / initial_price - 1) / target_supply
+fn design_sigmoid(targets: &LaunchTargets) -> Result<BondingCurveType, CurveError> {
+    let max_price = targets
+        .target_price
+        .checked_mul(2)
+        .ok_or(CurveError::Overflow)?;
+    let ln_odds = div_wad(max_price, targets.initial_price, Rounding::Down)
+        .and_then(|x| ln_wad(x - WAD))
+        .filter(|x| *x > 0)
+        .ok_or(CurveError::InvalidParameters)?;
+    let steepness = mul_div(ln_odds as u128, WAD, targets.target_supply, Rounding::Down)
+        .filter(|k| *k > 0)
+        .ok_or(CurveError::InvalidParameters)?;
+    Ok(BondingCurveType::Sigmoid {
+        max_price,
+        midpoint_supply: targets.target_supply,
+        steepness,
+    })
+}
+
+// Derives the parameters of a curve of `kind` from the launch targets, and
+// checks the curve can still be evaluated at the max supply and holds the
+// target raise, if any, at the target supply
+pub fn design_curve(
+    kind: CurveKind,
+    targets: &LaunchTargets,
+) -> Result<BondingCurveType, CurveError> {
+    validate_targets(targets)?;
+    let bonding_curve_type = match kind {
+        CurveKind::Linear => design_linear(targets)?,
+        CurveKind::Exponential => design_exponential(targets)?,
+        CurveKind::Power => design_power(targets)?,
+        CurveKind::ConstantProduct => design_constant_product(targets)?,
+        CurveKind::Stepped => design_stepped(targets)?,
+        CurveKind::Sigmoid => design_sigmoid(targets)?,
+    };
+    // The last tier of a stepped curve ends at the max supply, so there is no
+    // next token to price there
+    if kind != CurveKind::Stepped {
+        report_curve(&bonding_curve_type, targets.max_supply)?;
+    }
+    if let Some(target_raise) = targets.target_raise {
+        let reserve = curve_reserve_at_supply(&bonding_curve_type, targets.target_supply)?;
+        if reserve.abs_diff(target_raise) > target_raise / TARGET_RAISE_TOLERANCE {
+            return Err(CurveError::InvalidParameters);
+        }
+    }
+    Ok(bonding_curve_type)
+}
+
+struct Report {
+    supply: u128,
+}
+
+impl CurveOperation for Report {
+    type Output = CurveReport;
+
+    fn apply<C: BondingCurve>(self, params: C::Params) -> Result<CurveReport, CurveError>
+    where
+        C::Params: Clone,
+    {
+        let price = C::price(self.supply, params.clone())?;
+        let reserve = C::reserve_at_supply(self.supply, params)?;
+        Ok(CurveReport { price, reserve })
+    }
+}
+
+// Price of the next token and reserve held at `supply`
+pub fn report_curve(
+    bonding_curve_type: &BondingCurveType,
+    supply: u128,
+) -> Result<CurveReport, CurveError> {
+    dispatch_curve(bonding_curve_type, Report { supply })
+}
+
+struct ValidateParameters {
+    max_supply: u128,
+}
+
+impl CurveOperation for ValidateParameters {
+    type Output = ();
+
+    fn apply<C: BondingCurve>(self, params: C::Params) -> Result<(), CurveError>
+    where
+        C::Params: Clone,
+    {
+        C::validate_parameters(self.max_supply, params)
+    }
+}
+
+// Checks a project can launch with `bonding_curve_type` and still price its
+// tokens at `max_supply`
+pub fn validate_curve(
+    bonding_curve_type: &BondingCurveType,
+    max_supply: u128,
+) -> Result<(), CurveError> {
+    if let BondingCurveType::Stepped { tiers } = bonding_curve_type {
+        if tiers.len() > MAX_STEPPED_TIERS {
+            return Err(CurveError::InvalidParameters);
+        }
+    }
+    dispatch_curve(bonding_curve_type, ValidateParameters { max_supply })
+}
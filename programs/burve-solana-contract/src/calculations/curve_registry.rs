@@ -0,0 +1,223 @@
+// src/calculations/curve_registry.rs
+use crate::bonding_curve::*;
+use crate::constant_product_bonding_curve::*;
+use crate::curve_designer::*;
+use crate::exp_bonding_curve::*;
+use crate::linear_bonding_curve::*;
+use crate::power_bonding_curve::*;
+use crate::sigmoid_bonding_curve::*;
+use crate::stepped_bonding_curve::*;
+use crate::{stepped_tiers, BondingCurveType};
+
+// Every curve kind the program knows, in `CurveKind` order
+pub const ALL_CURVE_KINDS: [CurveKind; 6] = [
+    CurveKind::Linear,
+    CurveKind::Exponential,
+    CurveKind::Power,
+    CurveKind::ConstantProduct,
+    CurveKind::Stepped,
+    CurveKind::Sigmoid,
+];
+
+impl CurveKind {
+    // Bit of the kind in an enabled curves mask
+    pub const fn flag(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+// Mask with every kind in `kinds` enabled
+pub fn curve_kinds_mask(kinds: &[CurveKind]) -> u8 {
+    kinds.iter().fold(0, |mask, kind| mask | kind.flag())
+}
+
+pub fn curve_kind(bonding_curve_type: &BondingCurveType) -> CurveKind {
+    match bonding_curve_type {
+        BondingCurveType::Linear { .. } => CurveKind::Linear,
+        BondingCurveType::Exponential { .. } => CurveKind::Exponential,
+        BondingCurveType::Power { .. } => CurveKind::Power,
+        BondingCurveType::ConstantProduct { .. } => CurveKind::ConstantProduct,
+        BondingCurveType::Stepped { .. } => CurveKind::Stepped,
+        BondingCurveType::Sigmoid { .. } => CurveKind::Sigmoid,
+    }
+}
+
+// Something to compute on a curve without knowing which one, see `dispatch_curve`
+pub trait CurveOperation {
+    type Output;
+
+    fn apply<C: BondingCurve>(self, params: C::Params) -> Result<Self::Output, CurveError>
+    where
+        C::Params: Clone;
+}
+
+// The one place mapping each `BondingCurveType` variant to its `BondingCurve`
+// implementation and parameters. Adding a curve means adding its arm here and
+// in `curve_kind`.
+pub fn dispatch_curve<O: CurveOperation>(
+    bonding_curve_type: &BondingCurveType,
+    operation: O,
+) -> Result<O::Output, CurveError> {
+    match bonding_curve_type.clone() {
+        BondingCurveType::Linear { a, b } => {
+            operation.apply::<LinearMixedBondingSwap>(Parameters { a, b })
+        }
+        BondingCurveType::Exponential { a, b } => {
+            operation.apply::<ExpMixedBondingSwap>(Parameters { a, b })
+        }
+        BondingCurveType::Power { a, b } => {
+            operation.apply::<PowerMixedBondingSwap>(Parameters { a, b })
+        }
+        BondingCurveType::ConstantProduct {
+            virtual_token_reserve,
+            virtual_raising_reserve,
+        } => operation.apply::<ConstantProductMixedBondingSwap>(Parameters {
+            a: virtual_token_reserve,
+            b: virtual_raising_reserve,
+        }),
+        BondingCurveType::Stepped { tiers } => {
+            operation.apply::<SteppedMixedBondingSwap>(stepped_tiers(tiers))
+        }
+        BondingCurveType::Sigmoid {
+            max_price,
+            midpoint_supply,
+            steepness,
+        } => operation.apply::<SigmoidMixedBondingSwap>(SigmoidParameters {
+            max_price,
+            midpoint_supply,
+            steepness,
+        }),
+    }
+}
+
+struct MintAmount {
+    paid_amount: u128,
+    current_supply: u128,
+}
+
+impl CurveOperation for MintAmount {
+    type Output = (u128, u128);
+
+    fn apply<C: BondingCurve>(self, params: C::Params) -> Result<(u128, u128), CurveError> {
+        C::calculate_mint_amount_from_bonding_curve(self.paid_amount, self.current_supply, params)
+    }
+}
+
+struct BurnAmount {
+    token_amount: u128,
+    current_supply: u128,
+}
+
+impl CurveOperation for BurnAmount {
+    type Output = (u128, u128);
+
+    fn apply<C: BondingCurve>(self, params: C::Params) -> Result<(u128, u128), CurveError> {
+        C::calculate_burn_amount_from_bonding_curve(self.token_amount, self.current_supply, params)
+    }
+}
+
+struct CostToMintExact {
+    token_amount: u128,
+    current_supply: u128,
+}
+
+impl CurveOperation for CostToMintExact {
+    type Output = (u128, u128);
+
+    fn apply<C: BondingCurve>(self, params: C::Params) -> Result<(u128, u128), CurveError> {
+        C::cost_to_mint_exact(self.token_amount, self.current_supply, params)
+    }
+}
+
+struct TokensToBurnForExactOut {
+    raising_amount: u128,
+    current_supply: u128,
+}
+
+impl CurveOperation for TokensToBurnForExactOut {
+    type Output = (u128, u128);
+
+    fn apply<C: BondingCurve>(self, params: C::Params) -> Result<(u128, u128), CurveError> {
+        C::tokens_to_burn_for_exact_out(self.raising_amount, self.current_supply, params)
+    }
+}
+
+struct ReserveAtSupply {
+    current_supply: u128,
+}
+
+impl CurveOperation for ReserveAtSupply {
+    type Output = u128;
+
+    fn apply<C: BondingCurve>(self, params: C::Params) -> Result<u128, CurveError> {
+        C::reserve_at_supply(self.current_supply, params)
+    }
+}
+
+// `BondingCurve::calculate_mint_amount_from_bonding_curve` on any curve
+pub fn curve_mint_amount(
+    bonding_curve_type: &BondingCurveType,
+    paid_amount: u128,
+    current_supply: u128,
+) -> Result<(u128, u128), CurveError> {
+    dispatch_curve(
+        bonding_curve_type,
+        MintAmount {
+            paid_amount,
+            current_supply,
+        },
+    )
+}
+
+// `BondingCurve::calculate_burn_amount_from_bonding_curve` on any curve
+pub fn curve_burn_amount(
+    bonding_curve_type: &BondingCurveType,
+    token_amount: u128,
+    current_supply: u128,
+) -> Result<(u128, u128), CurveError> {
+    dispatch_curve(
+        bonding_curve_type,
+        BurnAmount {
+            token_amount,
+            current_supply,
+        },
+    )
+}
+
+// `BondingCurve::cost_to_mint_exact` on any curve
+pub fn curve_cost_to_mint_exact(
+    bonding_curve_type: &BondingCurveType,
+    token_amount: u128,
+    current_supply: u128,
+) -> Result<(u128, u128), CurveError> {
+    dispatch_curve(
+        bonding_curve_type,
+        CostToMintExact {
+            token_amount,
+            current_supply,
+        },
+    )
+}
+
+// `BondingCurve::tokens_to_burn_for_exact_out` on any curve
+pub fn curve_tokens_to_burn_for_exact_out(
+    bonding_curve_type: &BondingCurveType,
+    raising_amount: u128,
+    current_supply: u128,
+) -> Result<(u128, u128), CurveError> {
+    dispatch_curve(
+        bonding_curve_type,
+        TokensToBurnForExactOut {
+            raising_amount,
+            current_supply,
+        },
+    )
+}
+
+// `BondingCurve::reserve_at_supply` on any curve
+pub fn curve_reserve_at_supply(
+    bonding_curve_type: &BondingCurveType,
+    current_supply: u128,
+) -> Result<u128, CurveError> {
+    dispatch_curve(bonding_curve_type, ReserveAtSupply { current_supply })
+}
@@ -1,53 +1,167 @@
 // src/calculations/exp_bonding_curve.rs
 use crate::bonding_curve::*;
+use crate::math::*;
 
 pub struct ExpMixedBondingSwap;
 
+fn unpack_parameters(parameters: Parameters) -> Result<(u128, u128), CurveError> {
+    if parameters.a == 0 || parameters.b == 0 {
+        return Err(CurveError::InvalidParameters);
+    }
+    Ok((parameters.a, parameters.b))
+}
+
+// e ^ (tokenCurrentSupply / b), in WAD
+fn exp_of_supply(token_current_supply: u128, b: u128) -> Result<u128, CurveError> {
+    let e_index = mul_div(token_current_supply, WAD, b, Rounding::Down)
+        .and_then(|e_index| i128::try_from(e_index).ok())
+        .ok_or(CurveError::Overflow)?;
+    exp_wad(e_index).ok_or(CurveError::Overflow)
+}
+
+// Bounds around e ^ (tokenCurrentSupply / b) that cover the truncated exponent
+// and the error of `exp_wad`, so the inverse quotes never undercharge
+fn exp_of_supply_bounds(token_current_supply: u128, b: u128) -> Result<(u128, u128), CurveError> {
+    let exp_val = exp_of_supply(token_current_supply, b)?;
+    let margin = exp_val / 10_000_000_000_000_000 + 1;
+    let upper = exp_val.checked_add(margin).ok_or(CurveError::Overflow)?;
+    Ok((exp_val.saturating_sub(margin), upper))
+}
+
+// Absolute error bound of `ln_wad`
+const LN_ERROR: i128 = 100;
+
 impl BondingCurve for ExpMixedBondingSwap {
+    type Params = Parameters;
+
     // x => tokenAmount, y => raisingTokenAmount
     // y = (a) e**(x/b)
     // tokenAmount = b * ln(e ^ (tokenCurrentSupply / b) + raisingTokenAmount / a / b) - tokenCurrentSupply
     fn calculate_mint_amount_from_bonding_curve(
-        raising_token_amount: u64,
-        token_current_supply: u64,
+        raising_token_amount: u128,
+        token_current_supply: u128,
         parameters: Parameters,
-    ) -> (u64, u64) {
-        let (a, b) = (parameters.a as f64, parameters.b as f64);
-        // FIXME: Boundary check!!
-        let e_index = token_current_supply as f64 / b;
-        let e_mod = raising_token_amount as f64 / b / a * 1e9f64;
-        let exp_val = (e_index.exp() + e_mod).ln();
-        assert!(exp_val >= 0f64);
-        let token_amount = (exp_val * b).round() as u64 - token_current_supply;
-
-        (token_amount, raising_token_amount)
+    ) -> Result<(u128, u128), CurveError> {
+        let (a, b) = unpack_parameters(parameters)?;
+        let (exp_val, _) = exp_of_supply_bounds(token_current_supply, b)?;
+        let e_mod = mul_div(raising_token_amount, WAD, a, Rounding::Down)
+            .and_then(|x| mul_div(x, WAD, b, Rounding::Down))
+            .ok_or(CurveError::Overflow)?;
+        let ln_val = exp_val
+            .checked_add(e_mod)
+            .and_then(ln_wad)
+            .ok_or(CurveError::Overflow)?
+            - LN_ERROR;
+        let new_supply = if ln_val > 0 {
+            mul_div(ln_val as u128, b, WAD, Rounding::Down).ok_or(CurveError::Overflow)?
+        } else {
+            0
+        };
+        let token_amount = new_supply.saturating_sub(token_current_supply);
+        if token_amount == 0 {
+            return Err(CurveError::ZeroOutput);
+        }
+
+        Ok((token_amount, raising_token_amount))
     }
 
     // x => tokenAmount, y => raisingTokenAmount
     // y = (a) e**(x/b)
     // raisingTokenAmount = ab * (e ^ (tokenCurrentSupply / b) - e ^ ((tokenCurrentSupply - tokenAmount) / b))
     fn calculate_burn_amount_from_bonding_curve(
-        token_amount: u64,
-        token_current_supply: u64,
+        token_amount: u128,
+        token_current_supply: u128,
         parameters: Parameters,
-    ) -> (u64, u64) {
-        let (a, b) = (parameters.a as f64, parameters.b as f64);
-        let e_index_1 = token_current_supply as f64 / b;
-        let e_index_0 = (token_current_supply - token_amount) as f64 / b;
-        let exp_val1 = e_index_1.exp();
-        let exp_val0 = e_index_0.exp();
-        let y = exp_val1 - exp_val0;
-        assert!(y >= 0f64);
-        let raising_token_amount = (y * a * b / 1e9f64).round() as u64;
-        (token_amount, raising_token_amount)
+    ) -> Result<(u128, u128), CurveError> {
+        let (a, b) = unpack_parameters(parameters)?;
+        let remaining_supply = token_current_supply
+            .checked_sub(token_amount)
+            .ok_or(CurveError::InsufficientSupply)?;
+        let (exp_val1, _) = exp_of_supply_bounds(token_current_supply, b)?;
+        let (_, exp_val0) = exp_of_supply_bounds(remaining_supply, b)?;
+        let y = exp_val1.saturating_sub(exp_val0);
+        let raising_token_amount = mul_div(y, a, WAD, Rounding::Down)
+            .and_then(|x| mul_div(x, b, WAD, Rounding::Down))
+            .ok_or(CurveError::Overflow)?;
+        if raising_token_amount == 0 {
+            return Err(CurveError::ZeroOutput);
+        }
+
+        Ok((token_amount, raising_token_amount))
     }
 
     // price = a  * e ^ (tokenCurrentSupply / b)
-    fn price(token_current_supply: u64, parameters: Parameters) -> u64 {
-        let (a, b) = (parameters.a as f64, parameters.b as f64);
-        let e_index = token_current_supply as f64 / b;
-        let exp_val = e_index.exp();
-        assert!(exp_val >= 0f64);
-        (exp_val * a).round() as u64
+    fn price(token_current_supply: u128, parameters: Parameters) -> Result<u128, CurveError> {
+        let (a, b) = unpack_parameters(parameters)?;
+        let exp_val = exp_of_supply(token_current_supply, b)?;
+        mul_div(exp_val, a, WAD, Rounding::Down).ok_or(CurveError::Overflow)
+    }
+
+    // raisingTokenAmount = ab * (e ^ ((tokenCurrentSupply + tokenAmount) / b) - e ^ (tokenCurrentSupply / b))
+    fn cost_to_mint_exact(
+        token_amount: u128,
+        token_current_supply: u128,
+        parameters: Parameters,
+    ) -> Result<(u128, u128), CurveError> {
+        let (a, b) = unpack_parameters(parameters)?;
+        if token_amount == 0 {
+            return Err(CurveError::ZeroOutput);
+        }
+        let new_supply = token_current_supply
+            .checked_add(token_amount)
+            .ok_or(CurveError::Overflow)?;
+        // Minting back takes the error of `ln_wad` off the new supply and
+        // floors the quote twice, so quote a little further up the curve
+        let ln_slack = mul_div(LN_ERROR as u128 + 1, b, WAD, Rounding::Up)
+            .and_then(|x| x.checked_add(1))
+            .ok_or(CurveError::Overflow)?;
+        let (_, exp_val1) = new_supply
+            .checked_add(ln_slack)
+            .ok_or(CurveError::Overflow)
+            .and_then(|x| exp_of_supply_bounds(x, b))?;
+        let (exp_val0, _) = exp_of_supply_bounds(token_current_supply, b)?;
+        let y = (exp_val1 - exp_val0)
+            .checked_add(WAD / b + 2)
+            .ok_or(CurveError::Overflow)?;
+        let raising_token_amount = mul_div(y, a, WAD, Rounding::Up)
+            .and_then(|x| mul_div(x, b, WAD, Rounding::Up))
+            .ok_or(CurveError::Overflow)?;
+
+        Ok((token_amount, raising_token_amount))
+    }
+
+    // tokenAmount = tokenCurrentSupply - b * ln(e ^ (tokenCurrentSupply / b) - raisingTokenAmount / a / b)
+    fn tokens_to_burn_for_exact_out(
+        raising_token_amount: u128,
+        token_current_supply: u128,
+        parameters: Parameters,
+    ) -> Result<(u128, u128), CurveError> {
+        let (a, b) = unpack_parameters(parameters)?;
+        if raising_token_amount == 0 {
+            return Err(CurveError::ZeroOutput);
+        }
+        let (exp_val, _) = exp_of_supply_bounds(token_current_supply, b)?;
+        // Burning floors the proceeds twice and takes the upper bound of the
+        // exponential at the remaining supply, so quote a little more
+        let e_mod = raising_token_amount
+            .checked_add(b / WAD + 2)
+            .and_then(|x| mul_div(x, WAD, a, Rounding::Up))
+            .and_then(|x| mul_div(x, WAD, b, Rounding::Up))
+            .and_then(|x| x.checked_add(2 * (exp_val / 10_000_000_000_000_000 + 1)))
+            .ok_or(CurveError::Overflow)?;
+        // The remaining supply cannot go below zero, where the exponential is 1
+        let exp_remaining = exp_val
+            .checked_sub(e_mod)
+            .filter(|x| *x >= WAD)
+            .ok_or(CurveError::InsufficientSupply)?;
+        let ln_val = ln_wad(exp_remaining).ok_or(CurveError::Overflow)? - LN_ERROR;
+        let remaining_supply = if ln_val > 0 {
+            mul_div(ln_val as u128, b, WAD, Rounding::Down).ok_or(CurveError::Overflow)?
+        } else {
+            0
+        };
+        let token_amount = token_current_supply.saturating_sub(remaining_supply);
+
+        Ok((token_amount, raising_token_amount))
     }
 }
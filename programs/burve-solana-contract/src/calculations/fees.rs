@@ -0,0 +1,159 @@
+// src/calculations/fees.rs
+use crate::bonding_curve::*;
+use crate::math::*;
+use crate::{BurveBase, ProjectMetadata, MAX_REVENUE_RECIPIENTS, MAX_TAX_RATE_DENOMINATOR};
+
+// Burve and project tax on one side of a trade, out of MAX_TAX_RATE_DENOMINATOR
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaxRates {
+    pub burve: u16,
+    pub project: u16,
+}
+
+impl TaxRates {
+    pub fn mint(burve_base: &BurveBase, project_metadata: &ProjectMetadata) -> Self {
+        Self {
+            burve: burve_base.mint_tax,
+            project: project_metadata.mint_tax,
+        }
+    }
+
+    pub fn burn(burve_base: &BurveBase, project_metadata: &ProjectMetadata) -> Self {
+        Self {
+            burve: burve_base.burn_tax,
+            project: project_metadata.burn_tax,
+        }
+    }
+
+    fn total(&self) -> u64 {
+        self.burve as u64 + self.project as u64
+    }
+}
+
+// Share of the Burve fee paid to referrers on a project's trades
+pub fn referral_share(burve_base: &BurveBase, project_metadata: &ProjectMetadata) -> u16 {
+    project_metadata
+        .referral_share
+        .unwrap_or(burve_base.referral_share)
+}
+
+// Part of `burve_fee` the referrer earns, rounded down so the referrer is never
+// paid more than its share
+pub fn referral_fee(burve_fee: u64, share: u16) -> Result<u64, CurveError> {
+    if share as u64 > MAX_TAX_RATE_DENOMINATOR {
+        return Err(CurveError::InvalidParameters);
+    }
+    mul_div(
+        burve_fee as u128,
+        share as u128,
+        MAX_TAX_RATE_DENOMINATOR as u128,
+        Rounding::Down,
+    )
+    .and_then(|fee| u64::try_from(fee).ok())
+    .ok_or(CurveError::Overflow)
+}
+
+// A taxed amount split into the fees and what is left once they are taken
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeBreakdown {
+    pub gross_amount: u64,
+    pub burve_fee: u64,
+    pub project_fee: u64,
+    pub net_amount: u64,
+}
+
+// Tax taken on `amount`, rounded up in the protocol's favour
+pub fn tax_amount(amount: u64, tax: u16) -> Result<u64, CurveError> {
+    mul_div(
+        amount as u128,
+        tax as u128,
+        MAX_TAX_RATE_DENOMINATOR as u128,
+        Rounding::Up,
+    )
+    .and_then(|fee| u64::try_from(fee).ok())
+    .ok_or(CurveError::Overflow)
+}
+
+// Takes both taxes from `gross_amount`, e.g. from a payment or from the
+// proceeds of a burn
+pub fn fees_from_gross(gross_amount: u64, rates: TaxRates) -> Result<FeeBreakdown, CurveError> {
+    if rates.total() >= MAX_TAX_RATE_DENOMINATOR {
+        return Err(CurveError::InvalidParameters);
+    }
+    let burve_fee = tax_amount(gross_amount, rates.burve)?;
+    let project_fee = tax_amount(gross_amount, rates.project)?;
+    // Both fees round up, so a tiny amount may not cover them
+    let net_amount = gross_amount
+        .checked_sub(burve_fee)
+        .and_then(|amount| amount.checked_sub(project_fee))
+        .ok_or(CurveError::ZeroOutput)?;
+    Ok(FeeBreakdown {
+        gross_amount,
+        burve_fee,
+        project_fee,
+        net_amount,
+    })
+}
+
+// Smallest gross amount that still leaves `net_amount` once both taxes are
+// taken, e.g. the payment for an exact curve cost
+pub fn fees_for_net(net_amount: u64, rates: TaxRates) -> Result<FeeBreakdown, CurveError> {
+    if rates.total() >= MAX_TAX_RATE_DENOMINATOR {
+        return Err(CurveError::InvalidParameters);
+    }
+    let mut gross_amount = mul_div(
+        net_amount as u128,
+        MAX_TAX_RATE_DENOMINATOR as u128,
+        (MAX_TAX_RATE_DENOMINATOR - rates.total()) as u128,
+        Rounding::Up,
+    )
+    .and_then(|amount| u64::try_from(amount).ok())
+    .ok_or(CurveError::Overflow)?;
+    // Each tax rounds up by at most one unit, so this runs at most twice
+    loop {
+        match fees_from_gross(gross_amount, rates) {
+            Ok(breakdown) if breakdown.net_amount >= net_amount => return Ok(breakdown),
+            Ok(_) | Err(CurveError::ZeroOutput) => {
+                gross_amount = gross_amount.checked_add(1).ok_or(CurveError::Overflow)?;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+// A revenue split weighs each recipient out of MAX_TAX_RATE_DENOMINATOR, and
+// the weights have to add up to all of it
+pub fn validate_split_weights(weights: &[u16]) -> Result<(), CurveError> {
+    if weights.is_empty()
+        || weights.len() > MAX_REVENUE_RECIPIENTS
+        || weights.contains(&0)
+        || weights.iter().map(|weight| *weight as u64).sum::<u64>() != MAX_TAX_RATE_DENOMINATOR
+    {
+        return Err(CurveError::InvalidParameters);
+    }
+    Ok(())
+}
+
+// Shares of `amount` by weight, rounded down, with the rounding dust going to
+// the last recipient so the whole amount is paid out
+pub fn split_by_weights(amount: u64, weights: &[u16]) -> Result<Vec<u64>, CurveError> {
+    validate_split_weights(weights)?;
+    let mut shares = weights
+        .iter()
+        .map(|weight| {
+            mul_div(
+                amount as u128,
+                *weight as u128,
+                MAX_TAX_RATE_DENOMINATOR as u128,
+                Rounding::Down,
+            )
+            .and_then(|share| u64::try_from(share).ok())
+            .ok_or(CurveError::Overflow)
+        })
+        .collect::<Result<Vec<u64>, CurveError>>()?;
+    let paid: u64 = shares.iter().sum();
+    if let Some(last) = shares.last_mut() {
+        *last += amount - paid;
+    }
+    Ok(shares)
+}
@@ -0,0 +1,66 @@
+// src/calculations/launch_auction.rs
+use crate::bonding_curve::*;
+use crate::math::*;
+
+// Launches can open at up to 100 times the curve's price
+pub const MAX_LAUNCH_MULTIPLIER: u128 = 100 * WAD;
+
+// Dutch auction over the first moments of a launch, in WAD: mints pay the
+// curve's price times a premium that starts at `start_multiplier` and decays
+// linearly to 1 over `duration` seconds. Burns always use the plain curve, so
+// the premium is no part of the reserve: it accrues to the project like its
+// fees, see `launch_premium_fee`.
+pub fn validate_launch_auction(start_multiplier: u128, duration: i64) -> Result<(), CurveError> {
+    if !(WAD..=MAX_LAUNCH_MULTIPLIER).contains(&start_multiplier) || duration <= 0 {
+        return Err(CurveError::InvalidParameters);
+    }
+    Ok(())
+}
+
+// premium(t) = 1 + (start_multiplier - 1) * (1 - elapsed / duration), rounded
+// up, and 1 once the auction is over
+pub fn launch_premium(
+    start_multiplier: u128,
+    elapsed: i64,
+    duration: i64,
+) -> Result<u128, CurveError> {
+    validate_launch_auction(start_multiplier, duration)?;
+    if elapsed >= duration {
+        return Ok(WAD);
+    }
+    // A clock behind the launch time still charges the full premium
+    let remaining = (duration - elapsed.max(0)) as u128;
+    mul_div(
+        start_multiplier - WAD,
+        remaining,
+        duration as u128,
+        Rounding::Up,
+    )
+    .map(|premium| premium + WAD)
+    .ok_or(CurveError::Overflow)
+}
+
+// Part of a payment that goes to the curve once the premium is taken out,
+// rounded down
+pub fn without_launch_premium(amount: u128, premium: u128) -> Result<u128, CurveError> {
+    mul_div(amount, WAD, premium, Rounding::Down).ok_or(CurveError::Overflow)
+}
+
+// A curve cost with the premium added, rounded up
+pub fn with_launch_premium(cost: u128, premium: u128) -> Result<u128, CurveError> {
+    mul_div(cost, premium, WAD, Rounding::Up).ok_or(CurveError::Overflow)
+}
+
+// Raising token units of `paid_amount` the premium took, once `curve_amount`,
+// in WAD, went to the curve. The curve's part rounds up so the vault always
+// covers it.
+pub fn launch_premium_fee(
+    paid_amount: u64,
+    curve_amount: u128,
+    decimals: TokenDecimals,
+) -> Result<u64, CurveError> {
+    let curve_amount = decimals.raising_from_wad(curve_amount, Rounding::Up)?;
+    paid_amount
+        .checked_sub(curve_amount)
+        .ok_or(CurveError::InvalidParameters)
+}
@@ -1,55 +1,211 @@
 // src/calculations/linear_bonding_curve.rs
 use crate::bonding_curve::*;
-use std::ops::{Add, Div, Mul, Sub};
+use crate::math::*;
 
 pub struct LinearMixedBondingSwap;
 
+// Extra precision for the square root in the mint path
+const SQRT_SCALE: u128 = 1_000_000_000;
+
+// Prices are quoted in whole raising tokens per whole project token, in WAD:
+// k => price increase per whole token minted (parameters.a)
+// p => price at zero supply (parameters.b)
+// price(x) = k * x + p
+// reserve(x) = k * x^2 / 2 + p * x
+fn unpack_parameters(parameters: Parameters) -> Result<(u128, u128), CurveError> {
+    if parameters.a == 0 && parameters.b == 0 {
+        return Err(CurveError::InvalidParameters);
+    }
+    Ok((parameters.a, parameters.b))
+}
+
+// 2 * Δy / (sqrt(price(x)^2 + 2 * k * Δy) + price(x)), with the price and the
+// root taken at `scale` times the WAD precision. Rounding the price and the
+// root up only ever shrinks the amount minted.
+fn mint_amount_at_scale(
+    raising_token_amount: u128,
+    token_current_supply: u128,
+    k: u128,
+    p: u128,
+    scale: u128,
+) -> Option<u128> {
+    let scaled_price = mul_div(k, token_current_supply, WAD / scale, Rounding::Up)?
+        .checked_add(p.checked_mul(scale)?)?;
+    let root = sqrt_sum_of_products(
+        scaled_price,
+        scaled_price,
+        k.checked_mul(2 * scale)?,
+        raising_token_amount.checked_mul(scale)?,
+        Rounding::Up,
+    )?;
+    mul_div(
+        raising_token_amount.checked_mul(2 * scale)?,
+        WAD,
+        root.checked_add(scaled_price)?,
+        Rounding::Down,
+    )
+}
+
+// 2 * Δy / (price(x) + sqrt(price(x)^2 - 2 * k * Δy)), the tokens to burn for
+// Δy. Rounding the price and the root down only ever grows the amount burned.
+fn burn_amount_at_scale(
+    raising_token_amount: u128,
+    token_current_supply: u128,
+    k: u128,
+    p: u128,
+    scale: u128,
+) -> Option<u128> {
+    let scaled_price = mul_div(k, token_current_supply, WAD / scale, Rounding::Down)?
+        .checked_add(p.checked_mul(scale)?)?;
+    let root = sqrt_diff_of_products(
+        scaled_price,
+        scaled_price,
+        k.checked_mul(2 * scale)?,
+        raising_token_amount.checked_mul(scale)?,
+        Rounding::Down,
+    );
+    mul_div(
+        raising_token_amount.checked_mul(2 * scale)?,
+        WAD,
+        root.checked_add(scaled_price)?,
+        Rounding::Up,
+    )
+}
+
 impl BondingCurve for LinearMixedBondingSwap {
+    type Params = Parameters;
+
     // x => spl, y => native
-    // p(x) = (kx^2)/2+px-Δy
+    // Δy = reserve(x + Δx) - reserve(x)
+    // Δx = (sqrt(price(x)^2 + 2 * k * Δy) - price(x)) / k
+    //    = 2 * Δy / (sqrt(price(x)^2 + 2 * k * Δy) + price(x))
     fn calculate_mint_amount_from_bonding_curve(
-        raising_token_amount: u64,
-        token_current_supply: u64,
+        raising_token_amount: u128,
+        token_current_supply: u128,
         parameters: Parameters,
-    ) -> (u64, u64) {
-        let (k, p) = (parameters.a, parameters.b);
-        if k == 0u64 {
-            let token_amount = raising_token_amount.mul(1e9 as u64).div(p);
-            (token_amount, raising_token_amount)
-        } else {
-            let token_current_price = (token_current_supply as f64)
-                .mul(k as f64)
-                .div(1e9 as f64)
-                .add(p as f64);
-            let token_amount = (token_current_price
-                .mul(token_current_price)
-                .add(raising_token_amount.mul(2u64).mul(k) as f64)
-                .sqrt()
-                .sub(token_current_price))
-            .mul(1e9 as f64)
-            .div(k as f64)
-            .round() as u64;
-            (token_amount, raising_token_amount)
+    ) -> Result<(u128, u128), CurveError> {
+        let (k, p) = unpack_parameters(parameters)?;
+
+        // Low prices need the extra precision, high prices may not fit with it
+        let token_amount =
+            mint_amount_at_scale(raising_token_amount, token_current_supply, k, p, SQRT_SCALE)
+                .or_else(|| {
+                    mint_amount_at_scale(raising_token_amount, token_current_supply, k, p, 1)
+                })
+                .ok_or(CurveError::Overflow)?;
+        if token_amount == 0 {
+            return Err(CurveError::ZeroOutput);
         }
+
+        Ok((token_amount, raising_token_amount))
     }
 
+    // Δy = reserve(x) - reserve(x - Δx) = k * Δx * (2x - Δx) / 2 + p * Δx
     fn calculate_burn_amount_from_bonding_curve(
-        token_amount: u64,
-        token_current_supply: u64,
+        token_amount: u128,
+        token_current_supply: u128,
         parameters: Parameters,
-    ) -> (u64, u64) {
-        let (k, p) = (parameters.a, parameters.b);
-        let native_token_amount = token_current_supply
-            .mul(k)
-            .add(p.mul(1e9 as u64))
-            .mul(token_amount)
-            .sub(token_amount.mul(token_amount).mul(k).div(2u64))
-            .div(1e36 as u64);
-        (token_amount, native_token_amount)
+    ) -> Result<(u128, u128), CurveError> {
+        let (k, p) = unpack_parameters(parameters)?;
+        if token_amount > token_current_supply {
+            return Err(CurveError::InsufficientSupply);
+        }
+
+        let slope_part = token_current_supply
+            .checked_mul(2)
+            .and_then(|double_supply| {
+                mul_mul_div(
+                    token_amount,
+                    double_supply - token_amount,
+                    k,
+                    2 * WAD * WAD,
+                    Rounding::Down,
+                )
+            })
+            .ok_or(CurveError::Overflow)?;
+        let base_part =
+            mul_div(token_amount, p, WAD, Rounding::Down).ok_or(CurveError::Overflow)?;
+        let native_token_amount = slope_part
+            .checked_add(base_part)
+            .ok_or(CurveError::Overflow)?;
+        if native_token_amount == 0 {
+            return Err(CurveError::ZeroOutput);
+        }
+
+        Ok((token_amount, native_token_amount))
     }
 
-    fn price(token_current_supply: u64, parameters: Parameters) -> u64 {
-        let (k, p) = (parameters.a, parameters.b);
-        token_current_supply.mul(k).div(1e9 as u64).add(p)
+    fn price(token_current_supply: u128, parameters: Parameters) -> Result<u128, CurveError> {
+        let (k, p) = unpack_parameters(parameters)?;
+        mul_div(k, token_current_supply, WAD, Rounding::Down)
+            .and_then(|x| x.checked_add(p))
+            .ok_or(CurveError::Overflow)
+    }
+
+    // Δy = reserve(x + Δx) - reserve(x) = k * Δx * (2x + Δx) / 2 + p * Δx
+    fn cost_to_mint_exact(
+        token_amount: u128,
+        token_current_supply: u128,
+        parameters: Parameters,
+    ) -> Result<(u128, u128), CurveError> {
+        let (k, p) = unpack_parameters(parameters)?;
+        if token_amount == 0 {
+            return Err(CurveError::ZeroOutput);
+        }
+
+        let slope_part = token_current_supply
+            .checked_mul(2)
+            .and_then(|double_supply| double_supply.checked_add(token_amount))
+            .and_then(|x| mul_mul_div(token_amount, x, k, 2 * WAD * WAD, Rounding::Up))
+            .ok_or(CurveError::Overflow)?;
+        let base_part = mul_div(token_amount, p, WAD, Rounding::Up).ok_or(CurveError::Overflow)?;
+        let raising_token_amount = slope_part
+            .checked_add(base_part)
+            .ok_or(CurveError::Overflow)?;
+        if raising_token_amount == 0 {
+            return Err(CurveError::ZeroOutput);
+        }
+
+        Ok((token_amount, raising_token_amount))
+    }
+
+    // Δy = reserve(x) - reserve(x - Δx)
+    // Δx = (price(x) - sqrt(price(x)^2 - 2 * k * Δy)) / k
+    //    = 2 * Δy / (price(x) + sqrt(price(x)^2 - 2 * k * Δy))
+    fn tokens_to_burn_for_exact_out(
+        raising_token_amount: u128,
+        token_current_supply: u128,
+        parameters: Parameters,
+    ) -> Result<(u128, u128), CurveError> {
+        let (k, p) = unpack_parameters(parameters)?;
+        if raising_token_amount == 0 {
+            return Err(CurveError::ZeroOutput);
+        }
+
+        // Burning floors its slope and base parts separately, so quote for two
+        // wei more
+        let target_amount = raising_token_amount
+            .checked_add(2)
+            .ok_or(CurveError::Overflow)?;
+        // More than the reserve leaves the root at zero and the result above
+        // the supply
+        let token_amount =
+            burn_amount_at_scale(target_amount, token_current_supply, k, p, SQRT_SCALE)
+                .or_else(|| burn_amount_at_scale(target_amount, token_current_supply, k, p, 1))
+                .ok_or(CurveError::Overflow)?;
+        if token_amount > token_current_supply {
+            // The margin can overshoot when the whole reserve is asked for
+            let (_, reserve) = Self::calculate_burn_amount_from_bonding_curve(
+                token_current_supply,
+                token_current_supply,
+                Parameters { a: k, b: p },
+            )?;
+            if reserve < raising_token_amount {
+                return Err(CurveError::InsufficientSupply);
+            }
+            return Ok((token_current_supply, raising_token_amount));
+        }
+
+        Ok((token_amount, raising_token_amount))
     }
 }
@@ -1,25 +1,310 @@
+// src/calculations/math.rs
+//
+// Deterministic 18-decimal fixed-point helpers shared by the bonding curves.
+// Every value is a `u128` (or `i128` for signed inputs) scaled by `WAD`, so the
+// same inputs always produce the same outputs both on-chain and off-chain.
 use num_bigint::BigUint;
-use num_traits::{One, Zero};
-use std::ops::{Add, Div, Mul};
+use num_traits::{ToPrimitive, Zero};
 
 pub const PRECISION: usize = 18; // Precision for calculations
 
-pub fn e() -> BigUint {
-    BigUint::from(2718281828459045235u128) // Scaled e to PRECISION
+/// 1.0 in 18-decimal fixed point.
+pub const WAD: u128 = 1_000_000_000_000_000_000;
+const WAD_I: i128 = WAD as i128;
+
+/// ln(2) scaled by 1e36, used to keep range reduction exact to ~1e-18.
+const LN2_E36: i128 = 693_147_180_559_945_309_417_232_121_458_176_568;
+
+/// Below this input `exp_wad` is smaller than half a wei and returns zero.
+pub const MIN_EXP_INPUT: i128 = -42_139_678_854_452_767_621;
+/// Above this input `exp_wad` no longer fits into a `u128`.
+pub const MAX_EXP_INPUT: i128 = 47_276_307_437_780_177_293;
+
+/// Direction in which an inexact result is rounded.
+///
+/// Every inexact result rounds in the protocol's favour, so no sequence of
+/// trades can take more out of the vault than was paid in:
+/// - tokens minted and proceeds paid out round `Down`
+/// - costs charged, tokens burned for an exact output and fees round `Up`
+/// - where a function is only known to within an error bound, the bound on
+///   the protocol's side is used
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    Down,
+    Up,
+}
+
+/// `a * b / denominator` with a 256-bit intermediate product.
+///
+/// Returns `None` if `denominator` is zero or the result does not fit a `u128`.
+pub fn mul_div(a: u128, b: u128, denominator: u128, rounding: Rounding) -> Option<u128> {
+    if denominator == 0 {
+        return None;
+    }
+    let (quotient, has_remainder) = match a.checked_mul(b) {
+        Some(product) => (product / denominator, product % denominator != 0),
+        None => {
+            let product = BigUint::from(a) * BigUint::from(b);
+            let denominator = BigUint::from(denominator);
+            let has_remainder = !(&product % &denominator).is_zero();
+            ((product / denominator).to_u128()?, has_remainder)
+        }
+    };
+    if rounding == Rounding::Up && has_remainder {
+        quotient.checked_add(1)
+    } else {
+        Some(quotient)
+    }
 }
 
-pub fn ln(value: BigUint) -> BigUint {
-    if value == Zero::zero() {
-        return Zero::zero();
+/// `a * b * c / denominator` with a 384-bit intermediate product.
+///
+/// Returns `None` if `denominator` is zero or the result does not fit a `u128`.
+pub fn mul_mul_div(
+    a: u128,
+    b: u128,
+    c: u128,
+    denominator: u128,
+    rounding: Rounding,
+) -> Option<u128> {
+    if denominator == 0 {
+        return None;
     }
+    if let Some(product) = a.checked_mul(b) {
+        return mul_div(product, c, denominator, rounding);
+    }
+    let product = BigUint::from(a) * BigUint::from(b) * BigUint::from(c);
+    let denominator = BigUint::from(denominator);
+    let has_remainder = !(&product % &denominator).is_zero();
+    let quotient = (product / denominator).to_u128()?;
+    if rounding == Rounding::Up && has_remainder {
+        quotient.checked_add(1)
+    } else {
+        Some(quotient)
+    }
+}
+
+/// `a * b` for two 18-decimal fixed-point numbers.
+pub fn mul_wad(a: u128, b: u128, rounding: Rounding) -> Option<u128> {
+    mul_div(a, b, WAD, rounding)
+}
+
+/// `a / b` for two 18-decimal fixed-point numbers. Returns `None` for `b == 0`.
+pub fn div_wad(a: u128, b: u128, rounding: Rounding) -> Option<u128> {
+    mul_div(a, WAD, b, rounding)
+}
 
-    let mut result = BigUint::zero();
-    let mut n = value;
+/// Integer square root of a plain integer.
+pub fn sqrt(x: u128, rounding: Rounding) -> u128 {
+    if x < 2 {
+        return x;
+    }
+    // Newton iteration from an over-estimate converges to floor(sqrt(x))
+    let mut root = 1u128 << ((128 - x.leading_zeros()).div_ceil(2));
+    loop {
+        let next = (root + x / root) / 2;
+        if next >= root {
+            break;
+        }
+        root = next;
+    }
+    if rounding == Rounding::Up && root * root != x {
+        root + 1
+    } else {
+        root
+    }
+}
+
+fn sqrt_big(value: BigUint, rounding: Rounding) -> Option<u128> {
+    let root = value.sqrt();
+    let exact = &root * &root == value;
+    let root = root.to_u128()?;
+    if rounding == Rounding::Up && !exact {
+        root.checked_add(1)
+    } else {
+        Some(root)
+    }
+}
+
+/// Integer square root of `a * b`, computed without overflowing the product.
+pub fn sqrt_mul(a: u128, b: u128, rounding: Rounding) -> u128 {
+    match a.checked_mul(b) {
+        Some(product) => sqrt(product, rounding),
+        // sqrt(a * b) <= max(a, b), so the root always fits a u128
+        None => sqrt_big(BigUint::from(a) * BigUint::from(b), rounding).unwrap_or(u128::MAX),
+    }
+}
+
+/// Integer square root of `a * b + c * d`, computed with 256-bit intermediates.
+///
+/// Returns `None` when the root does not fit a `u128`.
+pub fn sqrt_sum_of_products(
+    a: u128,
+    b: u128,
+    c: u128,
+    d: u128,
+    rounding: Rounding,
+) -> Option<u128> {
+    match a
+        .checked_mul(b)
+        .and_then(|ab| ab.checked_add(c.checked_mul(d)?))
+    {
+        Some(sum) => Some(sqrt(sum, rounding)),
+        None => sqrt_big(
+            BigUint::from(a) * BigUint::from(b) + BigUint::from(c) * BigUint::from(d),
+            rounding,
+        ),
+    }
+}
+
+/// Integer square root of `a * b - c * d`, computed with 256-bit intermediates.
+///
+/// A negative difference saturates to zero. The root never exceeds
+/// `sqrt(a * b)`, so it always fits a `u128`.
+pub fn sqrt_diff_of_products(a: u128, b: u128, c: u128, d: u128, rounding: Rounding) -> u128 {
+    match (a.checked_mul(b), c.checked_mul(d)) {
+        (Some(ab), Some(cd)) => sqrt(ab.saturating_sub(cd), rounding),
+        _ => {
+            let ab = BigUint::from(a) * BigUint::from(b);
+            let cd = BigUint::from(c) * BigUint::from(d);
+            if cd >= ab {
+                return 0;
+            }
+            sqrt_big(ab - cd, rounding).unwrap_or(u128::MAX)
+        }
+    }
+}
+
+/// Square root of an 18-decimal fixed-point number.
+pub fn sqrt_wad(x: u128, rounding: Rounding) -> u128 {
+    sqrt_mul(x, WAD, rounding)
+}
+
+/// `x^n` for an 18-decimal fixed-point base and an integer exponent, using
+/// square-and-multiply. Every intermediate product is rounded in the same
+/// direction, so the result is a strict lower or upper bound.
+pub fn pow_wad_int(x: u128, n: u32, rounding: Rounding) -> Option<u128> {
+    let mut result = WAD;
+    let mut base = x;
+    let mut n = n;
+    while n > 0 {
+        if n & 1 == 1 {
+            result = mul_wad(result, base, rounding)?;
+        }
+        n >>= 1;
+        if n > 0 {
+            base = mul_wad(base, base, rounding)?;
+        }
+    }
+    Some(result)
+}
+
+/// `x^y` for an 18-decimal fixed-point base and exponent, as `exp(y * ln(x))`.
+///
+/// The error of `ln` is amplified by `y`, so the relative error of the result
+/// is below `(y + 1) * 1e-16`. Returns `None` when the result does not fit a
+/// `u128`.
+pub fn pow_wad(x: u128, y: u128) -> Option<u128> {
+    if y == 0 {
+        return Some(WAD);
+    }
+    if x == 0 {
+        return Some(0);
+    }
+    if y == WAD {
+        return Some(x);
+    }
+    let ln_x = ln_wad(x)?;
+    let y = i128::try_from(y).ok()?;
+    let exponent = if ln_x >= 0 {
+        i128::try_from(mul_wad(ln_x as u128, y as u128, Rounding::Down)?).ok()?
+    } else {
+        -i128::try_from(mul_wad(ln_x.unsigned_abs(), y as u128, Rounding::Down)?).ok()?
+    };
+    exp_wad(exponent)
+}
+
+/// e^x for a signed 18-decimal fixed-point `x`.
+///
+/// The input is reduced to `x = k * ln2 + r` with `|r| <= ln2 / 2`, e^r is
+/// evaluated with a Taylor series and the result is shifted by `2^k`. Every
+/// series term truncates by less than one wei, which bounds the error to 1e-17
+/// relative for results of at least 1.0 and to 10 wei below that. Returns
+/// `None` when the result does not fit into a `u128`.
+pub fn exp_wad(x: i128) -> Option<u128> {
+    if x < MIN_EXP_INPUT {
+        return Some(0);
+    }
+    if x > MAX_EXP_INPUT {
+        return None;
+    }
+
+    // k = round(x / ln2)
+    let x_e36 = x.checked_mul(WAD_I)?;
+    let k = (x_e36 + x_e36.signum() * LN2_E36 / 2) / LN2_E36;
+    let r = (x_e36 - k * LN2_E36) / WAD_I;
+
+    // e^r = sum(r^n / n!)
+    let mut sum = WAD_I;
+    let mut term = WAD_I;
+    let mut n = 1i128;
+    while term != 0 {
+        term = term * r / WAD_I / n;
+        sum += term;
+        n += 1;
+    }
+    let sum = sum as u128;
+
+    if k >= 0 {
+        let shift = k as u32;
+        if sum.leading_zeros() < shift {
+            return None;
+        }
+        Some(sum << shift)
+    } else {
+        Some(sum >> (-k) as u32)
+    }
+}
+
+/// Natural logarithm of a positive 18-decimal fixed-point `x`.
+///
+/// The input is normalised to `x = y * 2^k` with `y` in `[1, 2)`, and ln(y) is
+/// evaluated with the `2 * atanh((y - 1) / (y + 1))` series. The absolute error
+/// of the result is below 100 wei (1e-16). Returns `None` for `x == 0`.
+pub fn ln_wad(x: u128) -> Option<i128> {
+    if x == 0 {
+        return None;
+    }
+
+    // Find k such that WAD * 2^k <= x < WAD * 2^(k + 1)
+    let mut k = (127 - x.leading_zeros() as i32) - (127 - WAD.leading_zeros() as i32);
+    let normalize = |k: i32| {
+        if k >= 0 {
+            x >> k as u32
+        } else {
+            x << (-k) as u32
+        }
+    };
+    let mut y = normalize(k);
+    if y < WAD {
+        k -= 1;
+        y = normalize(k);
+    } else if y >= 2 * WAD {
+        k += 1;
+        y = normalize(k);
+    }
 
-    while n > One::one() {
-        n = n.div(e());
-        result = result.add(BigUint::one());
+    // ln(y) = 2 * (z + z^3 / 3 + z^5 / 5 + ...), z = (y - 1) / (y + 1)
+    let z = (y - WAD) * WAD / (y + WAD);
+    let z_squared = z * z / WAD;
+    let mut sum = z;
+    let mut term = z;
+    let mut n = 3u128;
+    while term != 0 {
+        term = term * z_squared / WAD;
+        sum += term / n;
+        n += 2;
     }
 
-    result
+    Some(k as i128 * LN2_E36 / WAD_I + 2 * sum as i128)
 }
@@ -1,9 +1,25 @@
 pub mod bonding_curve;
+pub mod constant_product_bonding_curve;
+pub mod curve_designer;
+pub mod curve_registry;
 pub mod exp_bonding_curve;
+pub mod fees;
+pub mod launch_auction;
 pub mod linear_bonding_curve;
-//pub mod math;
+pub mod math;
+pub mod power_bonding_curve;
+pub mod sigmoid_bonding_curve;
+pub mod stepped_bonding_curve;
 
 pub use bonding_curve::*;
+pub use constant_product_bonding_curve::*;
+pub use curve_designer::*;
+pub use curve_registry::*;
 pub use exp_bonding_curve::*;
+pub use fees::*;
+pub use launch_auction::*;
 pub use linear_bonding_curve::*;
-//pub use math::*;
+pub use math::*;
+pub use power_bonding_curve::*;
+pub use sigmoid_bonding_curve::*;
+pub use stepped_bonding_curve::*;
@@ -0,0 +1,186 @@
+// src/calculations/power_bonding_curve.rs
+use crate::bonding_curve::*;
+use crate::math::*;
+
+pub struct PowerMixedBondingSwap;
+
+// Below 1% the exponent, and with it the error of the logarithms, grows too large
+pub const MIN_RESERVE_RATIO: u128 = WAD / 100;
+
+// Bancor style constant reserve ratio curve, in WAD:
+// m => price of the first whole token (parameters.a)
+// r => reserve ratio, reserve / (supply * price), in (0, 1] (parameters.b)
+// price(x) = m * x ^ (1 / r - 1)
+// reserve(x) = r * x * price(x) = m * r * x ^ (1 / r)
+fn unpack_parameters(parameters: Parameters) -> Result<(u128, u128), CurveError> {
+    if parameters.a == 0 || parameters.b < MIN_RESERVE_RATIO || parameters.b > WAD {
+        return Err(CurveError::InvalidParameters);
+    }
+    Ok((parameters.a, parameters.b))
+}
+
+// Relative error bound of the logarithms for this exponent, (y + 2) * 1e-16,
+// plus a wei
+fn pow_error_margin(value: u128, exponent: u128) -> u128 {
+    mul_div(
+        value,
+        exponent.saturating_add(2 * WAD),
+        WAD * 10_000_000_000_000_000,
+        Rounding::Up,
+    )
+    .unwrap_or(u128::MAX)
+    .saturating_add(1)
+}
+
+// e ^ (ln_scale + y * ln(x)), i.e. scale * x ^ y without x ^ y having to fit
+fn scaled_pow(ln_scale: i128, x: u128, y: u128) -> Result<u128, CurveError> {
+    if x == 0 {
+        return Ok(0);
+    }
+    let ln_x = ln_wad(x).ok_or(CurveError::Overflow)?;
+    let exponent = mul_div(ln_x.unsigned_abs(), y, WAD, Rounding::Down)
+        .and_then(|x| i128::try_from(x).ok())
+        .map(|x| if ln_x < 0 { -x } else { x })
+        .and_then(|x| x.checked_add(ln_scale))
+        .ok_or(CurveError::Overflow)?;
+    exp_wad(exponent).ok_or(CurveError::Overflow)
+}
+
+// ln(m * r)
+fn ln_reserve_scale(m: u128, r: u128) -> Result<i128, CurveError> {
+    mul_div(m, r, WAD, Rounding::Down)
+        .and_then(ln_wad)
+        .ok_or(CurveError::InvalidParameters)
+}
+
+// Lower and upper bounds of reserve(x) = e ^ (ln(m * r) + ln(x) / r)
+fn reserve_bounds(supply: u128, m: u128, r: u128) -> Result<(u128, u128), CurveError> {
+    let exponent = div_wad(WAD, r, Rounding::Down).ok_or(CurveError::Overflow)?;
+    let reserve = scaled_pow(ln_reserve_scale(m, r)?, supply, exponent)?;
+    let margin = pow_error_margin(reserve, exponent);
+    let upper = reserve.checked_add(margin).ok_or(CurveError::Overflow)?;
+    Ok((reserve.saturating_sub(margin), upper))
+}
+
+// Lower bound of the supply at which the reserve reaches `reserve`,
+// x = (reserve / (m * r)) ^ r = e ^ (r * (ln(reserve) - ln(m * r)))
+fn supply_at_reserve(reserve: u128, m: u128, r: u128) -> Result<u128, CurveError> {
+    if reserve == 0 {
+        return Ok(0);
+    }
+    // e ^ (-r * ln(m * r)) * reserve ^ r
+    let ln_reserve_scale = ln_reserve_scale(m, r)?;
+    let ln_scale = mul_div(ln_reserve_scale.unsigned_abs(), r, WAD, Rounding::Down)
+        .and_then(|x| i128::try_from(x).ok())
+        .map(|x| if ln_reserve_scale < 0 { x } else { -x })
+        .ok_or(CurveError::Overflow)?;
+    let supply = scaled_pow(ln_scale, reserve, r)?;
+    Ok(supply.saturating_sub(pow_error_margin(supply, WAD)))
+}
+
+impl BondingCurve for PowerMixedBondingSwap {
+    type Params = Parameters;
+
+    // x => tokenAmount, y => raisingTokenAmount
+    // tokenAmount = ((reserve(x) + raisingTokenAmount) / (m * r)) ^ r - tokenCurrentSupply
+    fn calculate_mint_amount_from_bonding_curve(
+        raising_token_amount: u128,
+        token_current_supply: u128,
+        parameters: Parameters,
+    ) -> Result<(u128, u128), CurveError> {
+        let (m, r) = unpack_parameters(parameters)?;
+        let (reserve, _) = reserve_bounds(token_current_supply, m, r)?;
+        let new_reserve = reserve
+            .checked_add(raising_token_amount)
+            .ok_or(CurveError::Overflow)?;
+        let new_supply = supply_at_reserve(new_reserve, m, r)?;
+        let token_amount = new_supply.saturating_sub(token_current_supply);
+        if token_amount == 0 {
+            return Err(CurveError::ZeroOutput);
+        }
+
+        Ok((token_amount, raising_token_amount))
+    }
+
+    // raisingTokenAmount = reserve(tokenCurrentSupply) - reserve(tokenCurrentSupply - tokenAmount)
+    fn calculate_burn_amount_from_bonding_curve(
+        token_amount: u128,
+        token_current_supply: u128,
+        parameters: Parameters,
+    ) -> Result<(u128, u128), CurveError> {
+        let (m, r) = unpack_parameters(parameters)?;
+        let remaining_supply = token_current_supply
+            .checked_sub(token_amount)
+            .ok_or(CurveError::InsufficientSupply)?;
+        let (reserve1, _) = reserve_bounds(token_current_supply, m, r)?;
+        let (_, reserve0) = reserve_bounds(remaining_supply, m, r)?;
+        let raising_token_amount = reserve1.saturating_sub(reserve0);
+        if raising_token_amount == 0 {
+            return Err(CurveError::ZeroOutput);
+        }
+
+        Ok((token_amount, raising_token_amount))
+    }
+
+    // price = m * tokenCurrentSupply ^ (1 / r - 1)
+    fn price(token_current_supply: u128, parameters: Parameters) -> Result<u128, CurveError> {
+        let (m, r) = unpack_parameters(parameters)?;
+        let exponent = div_wad(WAD, r, Rounding::Down).ok_or(CurveError::Overflow)? - WAD;
+        if exponent == 0 {
+            return Ok(m);
+        }
+        scaled_pow(
+            ln_wad(m).ok_or(CurveError::Overflow)?,
+            token_current_supply,
+            exponent,
+        )
+    }
+
+    // raisingTokenAmount = reserve(tokenCurrentSupply + tokenAmount) - reserve(tokenCurrentSupply)
+    fn cost_to_mint_exact(
+        token_amount: u128,
+        token_current_supply: u128,
+        parameters: Parameters,
+    ) -> Result<(u128, u128), CurveError> {
+        let (m, r) = unpack_parameters(parameters)?;
+        if token_amount == 0 {
+            return Err(CurveError::ZeroOutput);
+        }
+        let new_supply = token_current_supply
+            .checked_add(token_amount)
+            .ok_or(CurveError::Overflow)?;
+        // Minting back takes the error margin off the new supply, so quote a
+        // little further up the curve
+        let (_, reserve1) = new_supply
+            .checked_add(2 * pow_error_margin(new_supply, WAD))
+            .ok_or(CurveError::Overflow)
+            .and_then(|x| reserve_bounds(x, m, r))?;
+        let (reserve0, _) = reserve_bounds(token_current_supply, m, r)?;
+
+        Ok((token_amount, reserve1 - reserve0))
+    }
+
+    // tokenAmount = tokenCurrentSupply - ((reserve(x) - raisingTokenAmount) / (m * r)) ^ r
+    fn tokens_to_burn_for_exact_out(
+        raising_token_amount: u128,
+        token_current_supply: u128,
+        parameters: Parameters,
+    ) -> Result<(u128, u128), CurveError> {
+        let (m, r) = unpack_parameters(parameters)?;
+        if raising_token_amount == 0 {
+            return Err(CurveError::ZeroOutput);
+        }
+        let (reserve, _) = reserve_bounds(token_current_supply, m, r)?;
+        // Burning takes the upper bound of the reserve left behind, so leave
+        // room for its error margin
+        let exponent = div_wad(WAD, r, Rounding::Down).ok_or(CurveError::Overflow)?;
+        let remaining_reserve = raising_token_amount
+            .checked_add(2 * pow_error_margin(reserve, exponent))
+            .and_then(|x| reserve.checked_sub(x))
+            .ok_or(CurveError::InsufficientSupply)?;
+        let remaining_supply = supply_at_reserve(remaining_reserve, m, r)?;
+        let token_amount = token_current_supply.saturating_sub(remaining_supply);
+
+        Ok((token_amount, raising_token_amount))
+    }
+}
@@ -0,0 +1,245 @@
+// src/calculations/sigmoid_bonding_curve.rs
+use crate::bonding_curve::*;
+use crate::math::*;
+
+pub struct SigmoidMixedBondingSwap;
+
+// Logistic curve, in WAD:
+// M => price the curve flattens out at (max_price)
+// m => supply at which the price is M / 2 (midpoint_supply)
+// k => steepness, per whole token (steepness)
+// z(x) = k * (x - m)
+// price(x) = M / (1 + e ^ -z(x))
+// reserve(x) = M / k * (softplus(z(x)) - softplus(z(0))), softplus(z) = ln(1 + e ^ z)
+#[derive(Debug, Clone, PartialEq)]
+pub struct SigmoidParameters {
+    pub max_price: u128,
+    pub midpoint_supply: u128,
+    pub steepness: u128,
+}
+
+// Absolute error bound of `softplus` and `inverse_softplus`: the error of
+// `ln_wad` plus that of `exp_wad` carried through the logarithm
+const SOFTPLUS_ERROR: u128 = 500;
+
+// Absolute error bound of `ln_wad`
+const LN_ERROR: i128 = 100;
+
+fn unpack_parameters(parameters: SigmoidParameters) -> Result<(u128, u128, u128), CurveError> {
+    if parameters.max_price == 0 || parameters.steepness == 0 {
+        return Err(CurveError::InvalidParameters);
+    }
+    Ok((
+        parameters.max_price,
+        parameters.midpoint_supply,
+        parameters.steepness,
+    ))
+}
+
+// z(x) = k * (x - m)
+fn exponent_at(supply: u128, m: u128, k: u128) -> Result<i128, CurveError> {
+    let magnitude = mul_div(supply.abs_diff(m), k, WAD, Rounding::Down)
+        .and_then(|z| i128::try_from(z).ok())
+        .ok_or(CurveError::Overflow)?;
+    Ok(if supply < m { -magnitude } else { magnitude })
+}
+
+// Supply at which z(x) = z, rounded down
+fn supply_at_exponent(z: i128, m: u128, k: u128) -> Result<u128, CurveError> {
+    if z < 0 {
+        let distance =
+            mul_div(z.unsigned_abs(), WAD, k, Rounding::Up).ok_or(CurveError::Overflow)?;
+        Ok(m.saturating_sub(distance))
+    } else {
+        mul_div(z as u128, WAD, k, Rounding::Down)
+            .and_then(|distance| m.checked_add(distance))
+            .ok_or(CurveError::Overflow)
+    }
+}
+
+// ln(1 + e ^ z), as z + ln(1 + e ^ -z) for positive z so the exponential stays below one
+fn softplus(z: i128) -> Result<u128, CurveError> {
+    let tail = exp_wad(-z.abs()).ok_or(CurveError::Overflow)?;
+    let ln_val = ln_wad(WAD + tail).ok_or(CurveError::Overflow)?.max(0) as u128;
+    if z > 0 {
+        (z as u128).checked_add(ln_val).ok_or(CurveError::Overflow)
+    } else {
+        Ok(ln_val)
+    }
+}
+
+// Lower bound of z = ln(e ^ u - 1), or None where e ^ u - 1 is lost to rounding
+fn inverse_softplus(u: u128) -> Result<Option<i128>, CurveError> {
+    let u = i128::try_from(u).map_err(|_| CurveError::Overflow)?;
+    if u >= WAD as i128 {
+        // u + ln(1 - e ^ -u)
+        let tail = exp_wad(-u).ok_or(CurveError::Overflow)?;
+        let tail = tail + tail / 10_000_000_000_000_000 + 1;
+        let ln_val = ln_wad(WAD - tail).ok_or(CurveError::Overflow)?;
+        Ok(Some(u + ln_val - LN_ERROR))
+    } else {
+        let exp_val = exp_wad(u).ok_or(CurveError::Overflow)?;
+        let exp_val = exp_val.saturating_sub(exp_val / 10_000_000_000_000_000 + 1);
+        match exp_val.checked_sub(WAD).filter(|x| *x > 0) {
+            Some(x) => Ok(Some(ln_wad(x).ok_or(CurveError::Overflow)? - LN_ERROR)),
+            None => Ok(None),
+        }
+    }
+}
+
+// Lower and upper bounds of reserve(x)
+fn reserve_bounds(
+    supply: u128,
+    m: u128,
+    k: u128,
+    max_price: u128,
+) -> Result<(u128, u128), CurveError> {
+    if supply == 0 {
+        return Ok((0, 0));
+    }
+    let sp1 = softplus(exponent_at(supply, m, k)?)?;
+    let sp0 = softplus(exponent_at(0, m, k)?)?;
+    let lower = mul_div(
+        sp1.saturating_sub(sp0 + 2 * SOFTPLUS_ERROR),
+        max_price,
+        k,
+        Rounding::Down,
+    );
+    let upper = mul_div(
+        (sp1 + 2 * SOFTPLUS_ERROR).saturating_sub(sp0),
+        max_price,
+        k,
+        Rounding::Up,
+    );
+    Ok((
+        lower.ok_or(CurveError::Overflow)?,
+        upper.ok_or(CurveError::Overflow)?,
+    ))
+}
+
+// Width of the bounds of `reserve_bounds`
+fn reserve_margin(k: u128, max_price: u128) -> Result<u128, CurveError> {
+    mul_div(4 * SOFTPLUS_ERROR + 1, max_price, k, Rounding::Up).ok_or(CurveError::Overflow)
+}
+
+// Lower bound of the supply at which the reserve reaches `reserve`
+fn supply_at_reserve(reserve: u128, m: u128, k: u128, max_price: u128) -> Result<u128, CurveError> {
+    if reserve == 0 {
+        return Ok(0);
+    }
+    let sp0 = softplus(exponent_at(0, m, k)?)?;
+    let u = mul_div(reserve, k, max_price, Rounding::Down)
+        .and_then(|x| x.checked_add(sp0))
+        .ok_or(CurveError::Overflow)?
+        .saturating_sub(2 * SOFTPLUS_ERROR);
+    match inverse_softplus(u)? {
+        Some(z) => supply_at_exponent(z, m, k),
+        None => Ok(0),
+    }
+}
+
+impl BondingCurve for SigmoidMixedBondingSwap {
+    type Params = SigmoidParameters;
+
+    // tokenAmount = reserve⁻¹(reserve(tokenCurrentSupply) + raisingTokenAmount) - tokenCurrentSupply
+    fn calculate_mint_amount_from_bonding_curve(
+        raising_token_amount: u128,
+        token_current_supply: u128,
+        parameters: SigmoidParameters,
+    ) -> Result<(u128, u128), CurveError> {
+        let (max_price, m, k) = unpack_parameters(parameters)?;
+        let (reserve, _) = reserve_bounds(token_current_supply, m, k, max_price)?;
+        let new_reserve = reserve
+            .checked_add(raising_token_amount)
+            .ok_or(CurveError::Overflow)?;
+        let new_supply = supply_at_reserve(new_reserve, m, k, max_price)?;
+        let token_amount = new_supply.saturating_sub(token_current_supply);
+        if token_amount == 0 {
+            return Err(CurveError::ZeroOutput);
+        }
+
+        Ok((token_amount, raising_token_amount))
+    }
+
+    // raisingTokenAmount = reserve(tokenCurrentSupply) - reserve(tokenCurrentSupply - tokenAmount)
+    fn calculate_burn_amount_from_bonding_curve(
+        token_amount: u128,
+        token_current_supply: u128,
+        parameters: SigmoidParameters,
+    ) -> Result<(u128, u128), CurveError> {
+        let (max_price, m, k) = unpack_parameters(parameters)?;
+        let remaining_supply = token_current_supply
+            .checked_sub(token_amount)
+            .ok_or(CurveError::InsufficientSupply)?;
+        let (reserve1, _) = reserve_bounds(token_current_supply, m, k, max_price)?;
+        let (_, reserve0) = reserve_bounds(remaining_supply, m, k, max_price)?;
+        let raising_token_amount = reserve1.saturating_sub(reserve0);
+        if raising_token_amount == 0 {
+            return Err(CurveError::ZeroOutput);
+        }
+
+        Ok((token_amount, raising_token_amount))
+    }
+
+    // price = M / (1 + e ^ -z(tokenCurrentSupply))
+    fn price(
+        token_current_supply: u128,
+        parameters: SigmoidParameters,
+    ) -> Result<u128, CurveError> {
+        let (max_price, m, k) = unpack_parameters(parameters)?;
+        let z = exponent_at(token_current_supply, m, k)?;
+        match exp_wad(-z).and_then(|tail| tail.checked_add(WAD)) {
+            Some(denominator) => {
+                mul_div(max_price, WAD, denominator, Rounding::Down).ok_or(CurveError::Overflow)
+            }
+            // Far below the midpoint the price rounds to zero
+            None => Ok(0),
+        }
+    }
+
+    // raisingTokenAmount = reserve(tokenCurrentSupply + tokenAmount) - reserve(tokenCurrentSupply),
+    // plus the margin minting it back loses
+    fn cost_to_mint_exact(
+        token_amount: u128,
+        token_current_supply: u128,
+        parameters: SigmoidParameters,
+    ) -> Result<(u128, u128), CurveError> {
+        let (max_price, m, k) = unpack_parameters(parameters)?;
+        if token_amount == 0 {
+            return Err(CurveError::ZeroOutput);
+        }
+        let new_supply = token_current_supply
+            .checked_add(token_amount)
+            .ok_or(CurveError::Overflow)?;
+        let (_, reserve1) = reserve_bounds(new_supply, m, k, max_price)?;
+        let (reserve0, _) = reserve_bounds(token_current_supply, m, k, max_price)?;
+        let raising_token_amount = reserve1
+            .checked_add(reserve_margin(k, max_price)?)
+            .ok_or(CurveError::Overflow)?
+            .saturating_sub(reserve0);
+
+        Ok((token_amount, raising_token_amount))
+    }
+
+    // tokenAmount = tokenCurrentSupply - reserve⁻¹(reserve(tokenCurrentSupply) - raisingTokenAmount),
+    // less the margin burning it loses
+    fn tokens_to_burn_for_exact_out(
+        raising_token_amount: u128,
+        token_current_supply: u128,
+        parameters: SigmoidParameters,
+    ) -> Result<(u128, u128), CurveError> {
+        let (max_price, m, k) = unpack_parameters(parameters)?;
+        if raising_token_amount == 0 {
+            return Err(CurveError::ZeroOutput);
+        }
+        let (reserve, _) = reserve_bounds(token_current_supply, m, k, max_price)?;
+        let remaining_reserve = raising_token_amount
+            .checked_add(reserve_margin(k, max_price)?)
+            .and_then(|x| reserve.checked_sub(x))
+            .ok_or(CurveError::InsufficientSupply)?;
+        let remaining_supply = supply_at_reserve(remaining_reserve, m, k, max_price)?;
+        let token_amount = token_current_supply.saturating_sub(remaining_supply);
+
+        Ok((token_amount, raising_token_amount))
+    }
+}
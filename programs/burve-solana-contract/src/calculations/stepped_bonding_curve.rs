@@ -0,0 +1,190 @@
+// src/calculations/stepped_bonding_curve.rs
+use crate::bonding_curve::*;
+use crate::math::*;
+
+pub struct SteppedMixedBondingSwap;
+
+// Fixed price tiers, in WAD. Each tier is (supply_breakpoint, price): tokens
+// from the previous breakpoint (or zero) up to `supply_breakpoint` sell at
+// `price`, and nothing can be minted past the last breakpoint.
+pub type StepTiers = Vec<(u128, u128)>;
+
+impl SteppedMixedBondingSwap {
+    // Breakpoints must strictly increase and every price must be positive
+    pub fn validate_tiers(tiers: &[(u128, u128)]) -> Result<(), CurveError> {
+        if tiers.is_empty() {
+            return Err(CurveError::InvalidParameters);
+        }
+        let mut previous_breakpoint = 0;
+        for &(supply_breakpoint, price) in tiers {
+            if supply_breakpoint <= previous_breakpoint || price == 0 {
+                return Err(CurveError::InvalidParameters);
+            }
+            previous_breakpoint = supply_breakpoint;
+        }
+        Ok(())
+    }
+}
+
+// Supply range [start, end) and price of every tier, starting from zero
+fn tier_ranges(tiers: &[(u128, u128)]) -> impl DoubleEndedIterator<Item = (u128, u128, u128)> + '_ {
+    tiers.iter().enumerate().map(move |(i, &(end, price))| {
+        let start = if i == 0 { 0 } else { tiers[i - 1].0 };
+        (start, end, price)
+    })
+}
+
+// Value of `token_amount` tokens at `price`
+fn tier_value(token_amount: u128, price: u128, rounding: Rounding) -> Result<u128, CurveError> {
+    mul_div(token_amount, price, WAD, rounding).ok_or(CurveError::Overflow)
+}
+
+impl BondingCurve for SteppedMixedBondingSwap {
+    type Params = StepTiers;
+
+    // Walks up the tiers from the current supply, buying out each tier until
+    // the raising amount runs out
+    fn calculate_mint_amount_from_bonding_curve(
+        raising_token_amount: u128,
+        token_current_supply: u128,
+        tiers: StepTiers,
+    ) -> Result<(u128, u128), CurveError> {
+        Self::validate_tiers(&tiers)?;
+        let mut remaining = raising_token_amount;
+        let mut token_amount = 0u128;
+        for (start, end, price) in tier_ranges(&tiers) {
+            if end <= token_current_supply {
+                continue;
+            }
+            let capacity = end - start.max(token_current_supply);
+            let tier_cost = tier_value(capacity, price, Rounding::Up)?;
+            if remaining < tier_cost {
+                token_amount +=
+                    mul_div(remaining, WAD, price, Rounding::Down).ok_or(CurveError::Overflow)?;
+                remaining = 0;
+                break;
+            }
+            remaining -= tier_cost;
+            token_amount += capacity;
+        }
+        // The last tier is sold out before the raising amount is used up
+        if remaining > 0 {
+            return Err(CurveError::InsufficientSupply);
+        }
+        if token_amount == 0 {
+            return Err(CurveError::ZeroOutput);
+        }
+
+        Ok((token_amount, raising_token_amount))
+    }
+
+    // Walks down the tiers from the current supply
+    fn calculate_burn_amount_from_bonding_curve(
+        token_amount: u128,
+        token_current_supply: u128,
+        tiers: StepTiers,
+    ) -> Result<(u128, u128), CurveError> {
+        Self::validate_tiers(&tiers)?;
+        let remaining_supply = token_current_supply
+            .checked_sub(token_amount)
+            .ok_or(CurveError::InsufficientSupply)?;
+        let mut raising_token_amount = 0u128;
+        for (start, end, price) in tier_ranges(&tiers).rev() {
+            let overlap = end
+                .min(token_current_supply)
+                .saturating_sub(start.max(remaining_supply));
+            raising_token_amount = raising_token_amount
+                .checked_add(tier_value(overlap, price, Rounding::Down)?)
+                .ok_or(CurveError::Overflow)?;
+        }
+        if raising_token_amount == 0 {
+            return Err(CurveError::ZeroOutput);
+        }
+
+        Ok((token_amount, raising_token_amount))
+    }
+
+    // Price of the tier the next token is minted from
+    fn price(token_current_supply: u128, tiers: StepTiers) -> Result<u128, CurveError> {
+        Self::validate_tiers(&tiers)?;
+        tier_ranges(&tiers)
+            .find(|&(_, end, _)| end > token_current_supply)
+            .map(|(_, _, price)| price)
+            .ok_or(CurveError::InsufficientSupply)
+    }
+
+    // Nothing can be minted past the last breakpoint, so the tiers bound the
+    // supply themselves and only the full reserve has to be representable
+    fn validate_parameters(_max_supply: u128, tiers: StepTiers) -> Result<(), CurveError> {
+        Self::validate_tiers(&tiers)?;
+        let last_breakpoint = tiers[tiers.len() - 1].0;
+        Self::reserve_at_supply(last_breakpoint, tiers)
+            .map(|_| ())
+            .map_err(|_| CurveError::InvalidParameters)
+    }
+
+    fn cost_to_mint_exact(
+        token_amount: u128,
+        token_current_supply: u128,
+        tiers: StepTiers,
+    ) -> Result<(u128, u128), CurveError> {
+        Self::validate_tiers(&tiers)?;
+        if token_amount == 0 {
+            return Err(CurveError::ZeroOutput);
+        }
+        let new_supply = token_current_supply
+            .checked_add(token_amount)
+            .ok_or(CurveError::Overflow)?;
+        let mut raising_token_amount = 0u128;
+        let mut covered_supply = token_current_supply;
+        for (start, end, price) in tier_ranges(&tiers) {
+            let overlap = end
+                .min(new_supply)
+                .saturating_sub(start.max(token_current_supply));
+            raising_token_amount = raising_token_amount
+                .checked_add(tier_value(overlap, price, Rounding::Up)?)
+                .ok_or(CurveError::Overflow)?;
+            covered_supply += overlap;
+        }
+        if covered_supply < new_supply {
+            return Err(CurveError::InsufficientSupply);
+        }
+
+        Ok((token_amount, raising_token_amount))
+    }
+
+    // Walks down the tiers, burning each one out until the proceeds are covered
+    fn tokens_to_burn_for_exact_out(
+        raising_token_amount: u128,
+        token_current_supply: u128,
+        tiers: StepTiers,
+    ) -> Result<(u128, u128), CurveError> {
+        Self::validate_tiers(&tiers)?;
+        if raising_token_amount == 0 {
+            return Err(CurveError::ZeroOutput);
+        }
+        let mut remaining = raising_token_amount;
+        let mut token_amount = 0u128;
+        for (start, end, price) in tier_ranges(&tiers).rev() {
+            if start >= token_current_supply {
+                continue;
+            }
+            let capacity = end.min(token_current_supply) - start;
+            let tier_proceeds = tier_value(capacity, price, Rounding::Down)?;
+            if remaining <= tier_proceeds {
+                token_amount +=
+                    mul_div(remaining, WAD, price, Rounding::Up).ok_or(CurveError::Overflow)?;
+                remaining = 0;
+                break;
+            }
+            remaining -= tier_proceeds;
+            token_amount += capacity;
+        }
+        // The whole supply does not cover the proceeds
+        if remaining > 0 {
+            return Err(CurveError::InsufficientSupply);
+        }
+
+        Ok((token_amount, raising_token_amount))
+    }
+}
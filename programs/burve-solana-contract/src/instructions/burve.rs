@@ -1,8 +1,9 @@
 use std::mem::size_of;
 
-use anchor_lang::prelude::*; 
+use anchor_lang::{prelude::*, Discriminator};
+use anchor_spl::token_interface::Mint;
 
-use crate::Errors;
+use crate::{curve_kinds_mask, update_account_lamports_to_minimum_balance, CurveKind, Errors, ProjectMetadata, ALL_CURVE_KINDS, MAX_TAX_RATE_DENOMINATOR, PROJECT_METADATA_SEED};
 
 const MAX_PLATFORM_TAX_RATE: u16 = 100;
 
@@ -16,6 +17,10 @@ pub fn burve_initialize(ctx: Context<BurveInitialize>, args: InitializeArgs) ->
 	ctx.accounts.burve_base.treasury = args.treasury;
     ctx.accounts.burve_base.mint_tax = 100;
     ctx.accounts.burve_base.burn_tax = 100;
+	// Curve kinds added later start disabled and are rolled out with
+	// `burve_set_curve_enabled`
+	ctx.accounts.burve_base.enabled_curves = curve_kinds_mask(&ALL_CURVE_KINDS);
+	ctx.accounts.burve_base.referral_share = 0;
     Ok(())
 }
 
@@ -56,6 +61,66 @@ pub fn burve_set_tax(ctx: Context<SetBurveTax>, args: SetBurveTaxArgs) -> Result
     Ok(())
 }
 
+#[derive(Accounts)]
+pub struct SetBurveCurveEnabled<'info> {
+	#[account(mut, has_one = admin @ Errors::SignerIsNotAdmin)]
+	pub burve_base: Account<'info, BurveBase>,
+	pub admin: Signer<'info>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+pub struct SetBurveCurveEnabledArgs {
+	pub kind: CurveKind,
+	pub enabled: bool,
+}
+
+// Projects can only be created with, or switched to, enabled curve kinds.
+// Disabling a kind leaves existing projects on it trading as before.
+pub fn burve_set_curve_enabled(ctx: Context<SetBurveCurveEnabled>, args: SetBurveCurveEnabledArgs) -> Result<()> {
+	if args.enabled {
+		ctx.accounts.burve_base.enabled_curves |= args.kind.flag();
+	} else {
+		ctx.accounts.burve_base.enabled_curves &= !args.kind.flag();
+	}
+	Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetBurveReferralShare<'info> {
+	#[account(mut, has_one = admin @ Errors::SignerIsNotAdmin)]
+	pub burve_base: Account<'info, BurveBase>,
+	pub admin: Signer<'info>,
+}
+
+pub fn burve_set_referral_share(ctx: Context<SetBurveReferralShare>, new_referral_share: u16) -> Result<()> {
+	require!(new_referral_share as u64 <= MAX_TAX_RATE_DENOMINATOR, Errors::TaxRateNotValid);
+	ctx.accounts.burve_base.referral_share = new_referral_share;
+	Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetProjectReferralShare<'info> {
+	#[account(has_one = admin @ Errors::SignerIsNotAdmin)]
+	pub burve_base: Account<'info, BurveBase>,
+	#[account(
+		mut,
+		seeds = [PROJECT_METADATA_SEED, mint.key().as_ref() ], 
+		bump 
+	)]
+	pub project_metadata: Account<'info, ProjectMetadata>,
+	pub mint: InterfaceAccount<'info, Mint>,
+	pub admin: Signer<'info>,
+}
+
+// The referral share comes out of the Burve fee, so only the Burve admin can
+// override it for a project. `None` falls back to BurveBase's share.
+pub fn burve_set_project_referral_share(ctx: Context<SetProjectReferralShare>, new_referral_share: Option<u16>) -> Result<()> {
+	if let Some(referral_share) = new_referral_share {
+		require!(referral_share as u64 <= MAX_TAX_RATE_DENOMINATOR, Errors::TaxRateNotValid);
+	}
+	ctx.accounts.project_metadata.referral_share = new_referral_share;
+	Ok(())
+}
 
 #[account]
 pub struct BurveBase {
@@ -63,6 +128,74 @@ pub struct BurveBase {
 	pub treasury: Pubkey,
     pub mint_tax: u16,
     pub burn_tax: u16,
+	// One `CurveKind::flag` bit per enabled curve kind
+	pub enabled_curves: u8,
+	// Share of the Burve fee paid to referrers, out of MAX_TAX_RATE_DENOMINATOR
+	pub referral_share: u16,
+}
+
+impl BurveBase {
+	pub fn curve_enabled(&self, kind: CurveKind) -> bool {
+		self.enabled_curves & kind.flag() != 0
+	}
+}
+
+// BurveBase as stored before `enabled_curves` and `referral_share`
+#[derive(AnchorDeserialize)]
+struct LegacyBurveBase {
+	admin: Pubkey,
+	treasury: Pubkey,
+	mint_tax: u16,
+	burn_tax: u16,
+}
+
+#[derive(Accounts)]
+pub struct MigrateBurveBase<'info> {
+	/// CHECK: still in the legacy layout, so it is read by hand
+	#[account(
+		mut,
+		owner = crate::ID,
+		seeds = [b"burve"], 
+		bump 
+	)]
+	pub burve_base: UncheckedAccount<'info>,
+	#[account(mut)]
+	pub admin: Signer<'info>,
+	pub system_program: Program<'info, System>,
+}
+
+// Grows a BurveBase created before `enabled_curves` and `referral_share` to the
+// current layout, with the defaults of `burve_initialize`
+pub fn burve_migrate(ctx: Context<MigrateBurveBase>) -> Result<()> {
+	let burve_base = ctx.accounts.burve_base.to_account_info();
+	let space = size_of::<BurveBase>() + 8;
+	require!(burve_base.data_len() < space, Errors::AccountAlreadyMigrated);
+
+	let legacy = {
+		let data = burve_base.try_borrow_data()?;
+		require!(data.starts_with(&BurveBase::DISCRIMINATOR), ErrorCode::AccountDiscriminatorMismatch);
+		LegacyBurveBase::deserialize(&mut &data[8..])
+			.map_err(|_| error!(ErrorCode::AccountDidNotDeserialize))?
+	};
+	require_keys_eq!(legacy.admin, ctx.accounts.admin.key(), Errors::SignerIsNotAdmin);
+
+	burve_base.realloc(space, true)?;
+	update_account_lamports_to_minimum_balance(
+		burve_base.clone(),
+		ctx.accounts.admin.to_account_info(),
+		ctx.accounts.system_program.to_account_info(),
+	)?;
+
+	let migrated = BurveBase {
+		admin: legacy.admin,
+		treasury: legacy.treasury,
+		mint_tax: legacy.mint_tax,
+		burn_tax: legacy.burn_tax,
+		enabled_curves: curve_kinds_mask(&ALL_CURVE_KINDS),
+		referral_share: 0,
+	};
+	let mut data = burve_base.try_borrow_mut_data()?;
+	migrated.try_serialize(&mut &mut data[..])
 }
 
 #[derive(Accounts)]
@@ -1,7 +1,9 @@
 pub mod burve;
+pub mod referral;
 pub mod route;
 pub mod token_factory;
 
 pub use burve::*;
+pub use referral::*;
 pub use route::*;
 pub use token_factory::*;
@@ -0,0 +1,207 @@
+use std::mem::size_of;
+
+use anchor_lang::{ prelude::*,  system_program};
+
+use anchor_spl::token_interface::{
+	transfer_checked, Mint, Token2022, TokenAccount, TransferChecked,
+};
+
+use crate::{
+	referral_fee, referral_share, BurveBase, Errors, ProjectMetadata, MINT_ACCOUNT_SEED,
+	PROJECT_METADATA_SEED,
+};
+
+pub const REFERRAL_SEED: &[u8] = b"referral";
+
+// What a referrer earned on one project's trades. The fees stay in the
+// project's vault, in its raising asset, until the referrer claims them.
+#[account]
+pub struct ReferralBalance {
+	pub referrer: Pubkey,
+	pub mint: Pubkey,
+	pub amount: u64,
+}
+
+#[derive(Accounts)]
+pub struct RegisterReferral<'info> {
+	#[account(
+		init,
+		payer = referrer,
+		space = size_of::<ReferralBalance>() + 8,
+		seeds = [REFERRAL_SEED, mint.key().as_ref(), referrer.key().as_ref()],
+		bump
+	)]
+	pub referral: Account<'info, ReferralBalance>,
+	pub mint: InterfaceAccount<'info, Mint>,
+	#[account(mut)]
+	pub referrer: Signer<'info>,
+	pub system_program: Program<'info, System>,
+}
+
+// Opens the balance a referrer passes to mints and burns of a project
+pub fn referral_register(ctx: Context<RegisterReferral>) -> Result<()> {
+	ctx.accounts.referral.referrer = ctx.accounts.referrer.key();
+	ctx.accounts.referral.mint = ctx.accounts.mint.key();
+	ctx.accounts.referral.amount = 0;
+	Ok(())
+}
+
+// Adds a trade's Burve fee to the Burve tax counter, less the referrer's share
+// when the trade came with a referral
+pub(crate) fn accrue_burve_fee(
+	burve_base: &BurveBase,
+	project_metadata: &mut ProjectMetadata,
+	referral: Option<&mut ReferralBalance>,
+	burve_fee: u64,
+) -> Result<()> {
+	let referral_fee = match referral {
+		Some(referral) => {
+			let fee = referral_fee(burve_fee, referral_share(burve_base, project_metadata))
+				.map_err(Errors::from)?;
+			referral.amount = referral.amount
+				.checked_add(fee)
+				.ok_or(error!(Errors::CounterOverflow))?;
+			project_metadata.referral_fee_counter = project_metadata.referral_fee_counter
+				.checked_add(fee)
+				.ok_or(error!(Errors::CounterOverflow))?;
+			fee
+		}
+		None => 0,
+	};
+
+	project_metadata.burve_tax_counter = project_metadata.burve_tax_counter
+		.checked_add(burve_fee - referral_fee)
+		.ok_or(error!(Errors::CounterOverflow))?;
+	Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(args: ClaimReferralFeesArgs)]
+pub struct ClaimReferralSPLFees<'info> {
+	#[account(
+		mut,
+		has_one = referrer,
+		seeds = [REFERRAL_SEED, mint.key().as_ref(), referrer.key().as_ref()],
+		bump
+	)]
+	pub referral: Account<'info, ReferralBalance>,
+	#[account(
+		mut,
+		constraint = project_metadata.raising_token == Some(raising_token.key()),
+		constraint = project_metadata.symbol == args.symbol,
+		seeds = [PROJECT_METADATA_SEED, mint.key().as_ref() ],
+		bump
+	)]
+	pub project_metadata: Box<Account<'info, ProjectMetadata>>,
+	#[account(
+		seeds = [MINT_ACCOUNT_SEED, args.symbol.as_bytes()],
+		bump,
+	)]
+	pub mint: InterfaceAccount<'info, Mint>,
+	#[account()]
+	pub raising_token: InterfaceAccount<'info, Mint>,
+	pub referrer: Signer<'info>,
+	#[account(
+		mut,
+		seeds = [b"vault", mint.key().as_ref()],
+		bump
+	)]
+	pub vault: InterfaceAccount<'info, TokenAccount>,
+	#[account(
+		mut,
+		token::mint = raising_token,
+		token::token_program = token_program,
+	)]
+	pub referrer_ata: InterfaceAccount<'info, TokenAccount>,
+	pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+pub struct ClaimReferralFeesArgs {
+	pub symbol: String,
+}
+
+pub fn referral_claim_spl_fees(ctx: Context<ClaimReferralSPLFees>, args: ClaimReferralFeesArgs) -> Result<()> {
+	let amount = ctx.accounts.referral.amount;
+
+	// Transfer referral fees to referrer
+	let token_program = ctx.accounts.token_program.to_account_info();
+	let accounts = TransferChecked {
+		from: ctx.accounts.vault.to_account_info().clone(),
+		to: ctx.accounts.referrer_ata.to_account_info().clone(),
+		authority: ctx.accounts.mint.to_account_info().clone(),
+		mint: ctx.accounts.raising_token.to_account_info().clone(),
+	};
+	let seeds = &[MINT_ACCOUNT_SEED, args.symbol.as_bytes(), &[ctx.bumps.mint]];
+	let signer = [&seeds[..]];
+
+	let cpi_ctx = CpiContext::new_with_signer(token_program, accounts, &signer);
+
+	transfer_checked(cpi_ctx, amount, ctx.accounts.raising_token.decimals)?;
+
+	// Reset referral balance
+	ctx.accounts.referral.amount = 0;
+	ctx.accounts.project_metadata.referral_fee_counter = ctx.accounts.project_metadata.referral_fee_counter
+		.checked_sub(amount)
+		.ok_or(error!(Errors::CounterOverflow))?;
+
+	Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(args: ClaimReferralFeesArgs)]
+pub struct ClaimReferralSOLFees<'info> {
+	#[account(
+		mut,
+		has_one = referrer,
+		seeds = [REFERRAL_SEED, mint.key().as_ref(), referrer.key().as_ref()],
+		bump
+	)]
+	pub referral: Account<'info, ReferralBalance>,
+	#[account(
+		mut,
+		constraint = project_metadata.raising_token.is_none(),
+		constraint = project_metadata.symbol == args.symbol,
+		seeds = [PROJECT_METADATA_SEED, mint.key().as_ref() ],
+		bump
+	)]
+	pub project_metadata: Box<Account<'info, ProjectMetadata>>,
+	#[account(
+		seeds = [MINT_ACCOUNT_SEED, args.symbol.as_bytes()],
+		bump,
+	)]
+	pub mint: InterfaceAccount<'info, Mint>,
+	#[account(mut)]
+	pub referrer: Signer<'info>,
+	#[account(
+		mut,
+		seeds = [b"vault", mint.key().as_ref()],
+		bump
+	)]
+	pub vault: SystemAccount<'info>,
+	pub system_program: Program<'info, System>,
+}
+
+pub fn referral_claim_sol_fees(ctx: Context<ClaimReferralSOLFees>, _args: ClaimReferralFeesArgs) -> Result<()> {
+	let amount = ctx.accounts.referral.amount;
+
+	// Transfer referral fees to referrer
+	let seeds = &[b"vault", ctx.accounts.mint.to_account_info().key.as_ref(), &[ctx.bumps.vault]];
+	let signer = [&seeds[..]];
+	let cpi_ctx = CpiContext::new_with_signer(
+	ctx.accounts.system_program.to_account_info(),
+	system_program::Transfer{
+		from: ctx.accounts.vault.to_account_info(),
+		to: ctx.accounts.referrer.to_account_info(),
+	}, &signer);
+
+	system_program::transfer(cpi_ctx, amount)?;
+
+	// Reset referral balance
+	ctx.accounts.referral.amount = 0;
+	ctx.accounts.project_metadata.referral_fee_counter = ctx.accounts.project_metadata.referral_fee_counter
+		.checked_sub(amount)
+		.ok_or(error!(Errors::CounterOverflow))?;
+
+	Ok(())
+}
@@ -10,65 +10,83 @@ use anchor_spl::{
 use anchor_spl::token_interface::{transfer_checked, TransferChecked};
 
 
-use crate::{ calculations::*, BurveBase, Errors, MAX_TAX_RATE_DENOMINATOR};
+use crate::{ calculations::*, BurveBase, Errors, SOL_DECIMALS};
 
 use crate::{
 	 MINT_ACCOUNT_SEED, PROJECT_METADATA_SEED
 };
 
+use crate::referral::*;
 use crate::token_factory::*;
 
 
+impl From<CurveError> for Errors {
+	fn from(err: CurveError) -> Self {
+		match err {
+			CurveError::Overflow => Errors::CurveOverflow,
+			CurveError::InsufficientSupply => Errors::InsufficientSupply,
+			CurveError::ZeroOutput => Errors::ZeroOutput,
+			CurveError::InvalidParameters => Errors::InvalidCurveParameters,
+		}
+	}
+}
+
 struct EstimateMintResult {
 	calculated_receiving_amount: u64,
+	// Includes the launch premium
 	actual_paid_amount: u64,
 	project_fee: u64,
 	burve_fee: u64,
+	launch_premium_fee: u64,
 }
 
+impl EstimateMintResult {
+	fn paid_amount(&self) -> u64 {
+		self.actual_paid_amount + self.project_fee + self.burve_fee
+	}
+}
 
 #[inline(never)]
 fn estimate_mint_amount_from_bonding_curve(
 	bonding_curve_type: BondingCurveType,
 	paid_amount: u64,
 	mint_supply: u64,
-	burve_tax: u16,
-	project_tax: u16,
-) -> EstimateMintResult {
-
-	let project_fee = paid_amount * project_tax as u64 / MAX_TAX_RATE_DENOMINATOR ;
-	let burve_fee = paid_amount * burve_tax as u64/ MAX_TAX_RATE_DENOMINATOR;
-
-	let actual_paid_amount = paid_amount - project_fee - burve_fee;
+	decimals: TokenDecimals,
+	tax_rates: TaxRates,
+	launch_premium: u128,
+) -> Result<EstimateMintResult> {
+
+	let fees = fees_from_gross(paid_amount, tax_rates).map_err(Errors::from)?;
+	let actual_paid_amount = fees.net_amount;
+	// The launch premium goes to the project, only the rest moves the curve
+	let curve_paid_amount = without_launch_premium(decimals.raising_to_wad(actual_paid_amount), launch_premium)
+		.map_err(Errors::from)?;
+	let launch_premium_fee = launch_premium_fee(actual_paid_amount, curve_paid_amount, decimals)
+		.map_err(Errors::from)?;
 
 	let (calculated_receiving_amount, _) = 
-	match bonding_curve_type {
-		BondingCurveType::Linear { a, b } => {
-			 LinearMixedBondingSwap::calculate_mint_amount_from_bonding_curve(
-				actual_paid_amount,	
-				mint_supply,
-				crate::Parameters { a, b },
-			)
-		}
-		BondingCurveType::Exponential { a, b } => {
-			 ExpMixedBondingSwap::calculate_mint_amount_from_bonding_curve(
-				actual_paid_amount,	
-				mint_supply,
-				crate::Parameters { a, b },
-			)
-		}
-
-	};
-
-	EstimateMintResult {
+	curve_mint_amount(
+		&bonding_curve_type,
+		curve_paid_amount,
+		decimals.project_to_wad(mint_supply),
+	).map_err(Errors::from)?;
+
+	let calculated_receiving_amount = decimals
+		.project_from_wad(calculated_receiving_amount, Rounding::Down)
+		.map_err(Errors::from)?;
+	require!(calculated_receiving_amount > 0, Errors::ZeroOutput);
+
+	Ok(EstimateMintResult {
 		calculated_receiving_amount,
 		actual_paid_amount,
-		project_fee,
-		burve_fee,
-	}
+		project_fee: fees.project_fee,
+		burve_fee: fees.burve_fee,
+		launch_premium_fee,
+	})
 }
 
 pub struct EstimateBurnResult {
+	burning_amount: u64,
 	actual_received_amount: u64,
 	project_fee: u64,
 	burve_fee: u64,
@@ -79,41 +97,327 @@ fn estimate_burn_amount_from_bonding_curve(
 	bonding_curve_type: BondingCurveType,
 	burning_amount: u64,
 	mint_supply: u64,
-	burve_tax: u16,
-	project_tax: u16,
-) -> EstimateBurnResult {
+	decimals: TokenDecimals,
+	tax_rates: TaxRates,
+) -> Result<EstimateBurnResult> {
 
 
 	let (calculated_receiving_amount, _) = 
-		match bonding_curve_type {
-			BondingCurveType::Linear { a, b } => {
-				 LinearMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
-					burning_amount,	
-					mint_supply,
-					crate::Parameters { a, b },
-				)
-			}
-			BondingCurveType::Exponential { a, b } => {
-				 ExpMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
-					burning_amount,	
-					mint_supply,
-					crate::Parameters { a, b },
-				)
-			}
-		};
+		curve_burn_amount(
+			&bonding_curve_type,
+			decimals.project_to_wad(burning_amount),
+			decimals.project_to_wad(mint_supply),
+		).map_err(Errors::from)?;
+
+	let calculated_receiving_amount = decimals
+		.raising_from_wad(calculated_receiving_amount, Rounding::Down)
+		.map_err(Errors::from)?;
+	require!(calculated_receiving_amount > 0, Errors::ZeroOutput);
+
+	let fees = fees_from_gross(calculated_receiving_amount, tax_rates).map_err(Errors::from)?;
+
+	Ok(EstimateBurnResult {
+		burning_amount,
+		actual_received_amount: fees.net_amount,
+		project_fee: fees.project_fee,
+		burve_fee: fees.burve_fee,
+	})
+}
+
+#[inline(never)]
+fn estimate_mint_cost_from_bonding_curve(
+	bonding_curve_type: BondingCurveType,
+	receiving_amount: u64,
+	mint_supply: u64,
+	decimals: TokenDecimals,
+	tax_rates: TaxRates,
+	launch_premium: u128,
+) -> Result<EstimateMintResult> {
+
+	let (_, curve_cost) = 
+	curve_cost_to_mint_exact(
+		&bonding_curve_type,
+		decimals.project_to_wad(receiving_amount),
+		decimals.project_to_wad(mint_supply),
+	).map_err(Errors::from)?;
+
+	let cost = with_launch_premium(curve_cost, launch_premium)
+		.and_then(|cost| decimals.raising_from_wad(cost, Rounding::Up))
+		.map_err(Errors::from)?;
+	let launch_premium_fee = launch_premium_fee(cost, curve_cost, decimals).map_err(Errors::from)?;
+
+	// Taxes are taken from the paid amount, so pay enough to leave the cost
+	let fees = fees_for_net(cost, tax_rates).map_err(Errors::from)?;
+
+	Ok(EstimateMintResult {
+		calculated_receiving_amount: receiving_amount,
+		actual_paid_amount: fees.net_amount,
+		project_fee: fees.project_fee,
+		burve_fee: fees.burve_fee,
+		launch_premium_fee,
+	})
+}
+
+#[inline(never)]
+fn estimate_burn_cost_from_bonding_curve(
+	bonding_curve_type: BondingCurveType,
+	receiving_amount: u64,
+	mint_supply: u64,
+	decimals: TokenDecimals,
+	tax_rates: TaxRates,
+) -> Result<EstimateBurnResult> {
+
+	// Taxes are taken from the proceeds, so release enough to leave the receiving amount
+	let fees = fees_for_net(receiving_amount, tax_rates).map_err(Errors::from)?;
+
+	let (burning_amount, _) = 
+		curve_tokens_to_burn_for_exact_out(
+			&bonding_curve_type,
+			decimals.raising_to_wad(fees.gross_amount),
+			decimals.project_to_wad(mint_supply),
+		).map_err(Errors::from)?;
+
+	let burning_amount = decimals
+		.project_from_wad(burning_amount, Rounding::Up)
+		.map_err(Errors::from)?;
+
+	// Any rounding left over after the taxes stays in the vault
+	Ok(EstimateBurnResult {
+		burning_amount,
+		actual_received_amount: receiving_amount,
+		project_fee: fees.project_fee,
+		burve_fee: fees.burve_fee,
+	})
+}
+
+
+// Premium mints pay on the curve's price while the launch auction runs
+fn current_launch_premium(launch_auction: &Option<LaunchAuction>) -> Result<u128> {
+	match launch_auction {
+		Some(auction) => {
+			let elapsed = Clock::get()?.unix_timestamp.saturating_sub(auction.start_time);
+			let premium = launch_premium(auction.start_multiplier, elapsed, auction.duration)
+				.map_err(Errors::from)?;
+			Ok(premium)
+		}
+		None => Ok(WAD),
+	}
+}
+
+// Mints paying in an amount that would take the supply past the project's max
+// supply only fill up to it, and are charged the cost of the tokens actually
+// minted plus the taxes on that cost. The rest of the payment never leaves the
+// buyer.
+#[inline(never)]
+fn cap_mint_at_max_supply(
+	estimate_res: EstimateMintResult,
+	project_metadata: &ProjectMetadata,
+	mint_supply: u64,
+	decimals: TokenDecimals,
+	tax_rates: TaxRates,
+	launch_premium: u128,
+) -> Result<EstimateMintResult> {
+	let max_supply = match project_metadata.max_supply {
+		Some(max_supply) => max_supply,
+		None => return Ok(estimate_res),
+	};
+	let available = max_supply.saturating_sub(mint_supply);
+	require!(available > 0, Errors::MaxSupplyReached);
+	if estimate_res.calculated_receiving_amount <= available {
+		return Ok(estimate_res);
+	}
+
+	let capped_res = estimate_mint_cost_from_bonding_curve(
+		project_metadata.bonding_curve_type.clone(),
+		available,
+		mint_supply,
+		decimals,
+		tax_rates,
+		launch_premium,
+	)?;
+	// Fewer tokens never cost more than the full order
+	require!(capped_res.paid_amount() <= estimate_res.paid_amount(), Errors::MaxPayExceeded);
+	Ok(capped_res)
+}
+
+// Exact-output mints ask for exactly `token_amount` tokens, so they fail rather
+// than fill partially past the project's max supply
+fn check_max_supply(project_metadata: &ProjectMetadata, mint_supply: u64, token_amount: u64) -> Result<()> {
+	if let Some(max_supply) = project_metadata.max_supply {
+		let new_supply = mint_supply.checked_add(token_amount).ok_or(error!(Errors::MaxSupplyReached))?;
+		require!(new_supply <= max_supply, Errors::MaxSupplyReached);
+	}
+	Ok(())
+}
+
+// Emitted by the mint that brings the supply up to the project's max supply
+#[event]
+pub struct SupplyCapReached {
+	pub mint: Pubkey,
+	pub max_supply: u64,
+}
+
+// Raising amount the vault must hold to honour burning the whole supply, in
+// raising token units, rounded up
+#[inline(never)]
+fn required_vault_reserve(
+	bonding_curve_type: BondingCurveType,
+	mint_supply: u64,
+	decimals: TokenDecimals,
+) -> Result<u64> {
+	let reserve = curve_reserve_at_supply(
+		&bonding_curve_type,
+		decimals.project_to_wad(mint_supply),
+	).map_err(Errors::from)?;
+
+	Ok(decimals.raising_from_wad(reserve, Rounding::Up).map_err(Errors::from)?)
+}
+
+// Lamports in a SOL vault above its rent exempt minimum
+fn sol_vault_balance(vault: &SystemAccount) -> Result<u64> {
+	let rent = Rent::get()?.minimum_balance(vault.data_len());
+	Ok(vault.lamports().saturating_sub(rent))
+}
+
+// Checked after every mint and burn: the vault, less the Burve tax and referral
+// fees it holds for claiming, must still cover the curve's reserve for the new
+// supply
+pub fn check_vault_solvency(
+	bonding_curve_type: BondingCurveType,
+	vault_balance: u64,
+	reserved_fees: u64,
+	mint_supply: u64,
+	decimals: TokenDecimals,
+) -> Result<()> {
+	let reserve = required_vault_reserve(bonding_curve_type, mint_supply, decimals)?;
+	let available = vault_balance.saturating_sub(reserved_fees);
+	require!(available >= reserve, Errors::VaultInsolvent);
+	Ok(())
+}
+
+// Project fees are held in the vault until they are claimed to the treasury,
+// or distributed to the revenue split
+fn accrue_project_fee(project_metadata: &mut ProjectMetadata, project_fee: u64) -> Result<()> {
+	project_metadata.project_tax_counter = project_metadata.project_tax_counter
+		.checked_add(project_fee)
+		.ok_or(error!(Errors::CounterOverflow))?;
+	Ok(())
+}
 
-	let project_fee = calculated_receiving_amount * project_tax as u64 / MAX_TAX_RATE_DENOMINATOR;
-	let burve_fee = calculated_receiving_amount * burve_tax as u64/ MAX_TAX_RATE_DENOMINATOR;
+// What a mint fixes, and the caller's limit on the other side of the trade
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub enum MintOrder {
+	// Pays in exactly `amount` of the raising token for at least `min_receive`
+	// tokens
+	ExactIn { min_receive: u64 },
+	// Mints exactly `amount` tokens for at most `max_pay` of the raising token
+	ExactOut { max_pay: u64 },
+}
+
+// What a burn fixes, and the caller's limit on the other side of the trade
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub enum BurnOrder {
+	// Burns exactly `amount` tokens for at least `min_receive` of the raising
+	// token
+	ExactIn { min_receive: u64 },
+	// Receives exactly `amount` of the raising token for at most `max_burn`
+	// tokens
+	ExactOut { max_burn: u64 },
+}
+
+// Prices a mint order on the project's curve and checks the caller's limit
+#[inline(never)]
+fn estimate_mint_order(
+	order: MintOrder,
+	amount: u64,
+	burve_base: &BurveBase,
+	project_metadata: &ProjectMetadata,
+	mint_supply: u64,
+	decimals: TokenDecimals,
+) -> Result<EstimateMintResult> {
+	let launch_premium = current_launch_premium(&project_metadata.launch_auction)?;
+	let tax_rates = TaxRates::mint(burve_base, project_metadata);
+	match order {
+		MintOrder::ExactIn { min_receive } => {
+			// Calculate how many tokens to mint
+			let estimate_res = estimate_mint_amount_from_bonding_curve(
+				project_metadata.bonding_curve_type.clone(),
+				amount,
+				mint_supply,
+				decimals,
+				tax_rates,
+				launch_premium,
+			)?;
+			let estimate_res = cap_mint_at_max_supply(
+				estimate_res,
+				project_metadata,
+				mint_supply,
+				decimals,
+				tax_rates,
+				launch_premium,
+			)?;
 
-	let actual_received_amount = calculated_receiving_amount - project_fee - burve_fee;
+			require!(estimate_res.calculated_receiving_amount >= min_receive, Errors::SlippageExceeded);
+			Ok(estimate_res)
+		}
+		MintOrder::ExactOut { max_pay } => {
+			check_max_supply(project_metadata, mint_supply, amount)?;
+			// Calculate how much to pay for the tokens
+			let estimate_res = estimate_mint_cost_from_bonding_curve(
+				project_metadata.bonding_curve_type.clone(),
+				amount,
+				mint_supply,
+				decimals,
+				tax_rates,
+				launch_premium,
+			)?;
 
-	EstimateBurnResult {
-		actual_received_amount,
-		project_fee,
-		burve_fee,
+			require!(estimate_res.paid_amount() <= max_pay, Errors::MaxPayExceeded);
+			Ok(estimate_res)
+		}
 	}
 }
 
+// Prices a burn order on the project's curve and checks the caller's limit
+#[inline(never)]
+fn estimate_burn_order(
+	order: BurnOrder,
+	amount: u64,
+	burve_base: &BurveBase,
+	project_metadata: &ProjectMetadata,
+	mint_supply: u64,
+	decimals: TokenDecimals,
+) -> Result<EstimateBurnResult> {
+	let tax_rates = TaxRates::burn(burve_base, project_metadata);
+	match order {
+		BurnOrder::ExactIn { min_receive } => {
+			// Calculate how much the burnt tokens return
+			let estimate_res = estimate_burn_amount_from_bonding_curve(
+				project_metadata.bonding_curve_type.clone(),
+				amount,
+				mint_supply,
+				decimals,
+				tax_rates,
+			)?;
+
+			require!(estimate_res.actual_received_amount >= min_receive, Errors::SlippageExceeded);
+			Ok(estimate_res)
+		}
+		BurnOrder::ExactOut { max_burn } => {
+			// Calculate how many tokens to burn for the proceeds
+			let estimate_res = estimate_burn_cost_from_bonding_curve(
+				project_metadata.bonding_curve_type.clone(),
+				amount,
+				mint_supply,
+				decimals,
+				tax_rates,
+			)?;
+
+			require!(estimate_res.burning_amount <= max_burn, Errors::MaxBurnExceeded);
+			Ok(estimate_res)
+		}
+	}
+}
 
 #[derive(Accounts)]
 #[instruction(args: MintTokenWithSPLArgs)]
@@ -124,9 +428,9 @@ pub struct MintTokenWithSPL<'info> {
 	)]
     pub burve_base: Box<Account<'info, BurveBase>>,
 	#[account(
+		mut,
 		constraint = project_metadata.raising_token == Some(raising_token.key()),
 		constraint = project_metadata.symbol == args.symbol,
-		constraint = project_metadata.treasury == project_treasury.key(),
 		seeds = [PROJECT_METADATA_SEED, mint.key().as_ref() ], 
 		bump 
 	)]
@@ -147,12 +451,6 @@ pub struct MintTokenWithSPL<'info> {
 		token::token_program = token_program
 	)]
 	pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
-	#[account(
-		mut,
-		token::mint = raising_token,
-		token::token_program = token_program,
-	)]
-	pub project_treasury: Box<InterfaceAccount<'info, TokenAccount>>,
     #[account(mut)]
     pub signer: Signer<'info>,
 	#[account(
@@ -170,6 +468,13 @@ pub struct MintTokenWithSPL<'info> {
 		associated_token::authority = signer,
 	)]
 	pub mint_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+	// Partner front-end the trade came through, see `referral_register`
+	#[account(
+		mut,
+		constraint = referral.mint == mint.key(),
+		constraint = referral.referrer != signer.key() @ Errors::SelfReferral,
+	)]
+	pub referral: Option<Box<Account<'info, ReferralBalance>>>,
 	pub system_program: Program<'info, System>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub token_program: Program<'info, Token2022>,
@@ -179,54 +484,45 @@ pub struct MintTokenWithSPL<'info> {
 pub struct MintTokenWithSPLArgs {
 	pub amount: u64,
 	pub symbol: String,
-	pub min_receive: u64,
+	pub order: MintOrder,
 }
 
 pub fn route_mint_token_with_spl(
 	ctx: Context<MintTokenWithSPL>,
 	args: MintTokenWithSPLArgs,
 ) -> Result<()> {
-	// Calculate how many tokens to mint
-	let estimate_res = estimate_mint_amount_from_bonding_curve(
-		ctx.accounts.project_metadata.bonding_curve_type.clone(),
+	let estimate_res = estimate_mint_order(
+		args.order,
 		args.amount,
+		&ctx.accounts.burve_base,
+		&ctx.accounts.project_metadata,
 		ctx.accounts.mint.supply,
-		ctx.accounts.burve_base.mint_tax,
-		ctx.accounts.project_metadata.mint_tax,
-	);
-
-	assert!(estimate_res.calculated_receiving_amount >= args.min_receive, "min_receive not met");
+		TokenDecimals::new(ctx.accounts.raising_token.decimals, ctx.accounts.mint.decimals).map_err(Errors::from)?,
+	)?;
 
 	// Transfer SPL token to vault
 	let token_program = ctx.accounts.token_program.to_account_info();
-	let accounts = TransferChecked {
+	let cpi_accounts = TransferChecked {
 		from: ctx.accounts.from_ata.to_account_info().clone(),
 		to: ctx.accounts.vault.to_account_info().clone(),
 		authority: ctx.accounts.signer.to_account_info().clone(),
 		mint: ctx.accounts.raising_token.to_account_info().clone(),
 	};
 
-	//let bump = &[ctx.bumps.mint];
-	//let seeds: &[&[u8]] = &[MINT_ACCOUNT_SEED, args.symbol.as_bytes(), bump];
-	//let signer_seeds = &[&seeds[..]];
-	let cpi_ctx = CpiContext::new(token_program, accounts);
-
-	transfer_checked(cpi_ctx, estimate_res.actual_paid_amount + estimate_res.burve_fee, ctx.accounts.raising_token.decimals)?;
-
-	ctx.accounts.project_metadata.burve_tax_counter += estimate_res.burve_fee;
-
-	// Transfer project tax to project treasury
-	let token_program = ctx.accounts.token_program.to_account_info();
-	let accounts = TransferChecked {
-		from: ctx.accounts.from_ata.to_account_info().clone(),
-		to: ctx.accounts.project_treasury.to_account_info().clone(),
-		authority: ctx.accounts.signer.to_account_info().clone(),
-		mint: ctx.accounts.raising_token.to_account_info().clone(),
-	};
+	let cpi_ctx = CpiContext::new(token_program, cpi_accounts);
 
-	let cpi_ctx = CpiContext::new(token_program, accounts);
+	// Both taxes stay in the vault until they are claimed
+	transfer_checked(cpi_ctx, estimate_res.paid_amount(), ctx.accounts.raising_token.decimals)?;
 
-	transfer_checked(cpi_ctx, estimate_res.project_fee, ctx.accounts.raising_token.decimals)?;
+	accrue_burve_fee(
+		&ctx.accounts.burve_base,
+		&mut ctx.accounts.project_metadata,
+		ctx.accounts.referral.as_deref_mut().map(|referral| &mut **referral),
+		estimate_res.burve_fee,
+	)?;
+	accrue_project_fee(&mut ctx.accounts.project_metadata, estimate_res.project_fee)?;
+	// The launch premium is the project's too
+	accrue_project_fee(&mut ctx.accounts.project_metadata, estimate_res.launch_premium_fee)?;
 
 	// Mint SPL token to mint token account
 	let seeds = &[MINT_ACCOUNT_SEED, args.symbol.as_bytes(), &[ctx.bumps.mint]];
@@ -245,7 +541,22 @@ pub fn route_mint_token_with_spl(
 		estimate_res.calculated_receiving_amount
 	)?;
 
-	Ok(())
+	ctx.accounts.mint.reload()?;
+	ctx.accounts.vault.reload()?;
+	if ctx.accounts.project_metadata.max_supply == Some(ctx.accounts.mint.supply) {
+		emit!(SupplyCapReached {
+			mint: ctx.accounts.mint.key(),
+			max_supply: ctx.accounts.mint.supply,
+		});
+	}
+
+	check_vault_solvency(
+		ctx.accounts.project_metadata.bonding_curve_type.clone(),
+		ctx.accounts.vault.amount,
+		ctx.accounts.project_metadata.reserved_fees()?,
+		ctx.accounts.mint.supply,
+		TokenDecimals::new(ctx.accounts.raising_token.decimals, ctx.accounts.mint.decimals).map_err(Errors::from)?,
+	)
 }
 
 #[derive(Accounts)]
@@ -257,9 +568,9 @@ pub struct BurnTokenToSPL<'info> {
 	)]
 	pub burve_base: Account<'info, BurveBase>,
 	#[account(
+		mut,
 		constraint = project_metadata.raising_token == Some(raising_token.key()),
 		constraint = project_metadata.symbol == args.symbol,
-		constraint = project_metadata.treasury == project_treasury.key(),
 		seeds = [PROJECT_METADATA_SEED, mint.key().as_ref() ], 
 		bump 
 	)]
@@ -278,12 +589,6 @@ pub struct BurnTokenToSPL<'info> {
 		bump
 	)]
 	pub vault: InterfaceAccount<'info, TokenAccount>,
-	#[account(
-		mut,
-		token::mint = raising_token,
-		token::token_program = token_program,
-	)]
-	pub project_treasury: InterfaceAccount<'info, TokenAccount>,
 	#[account(mut)]
 	pub signer: Signer<'info>,
 	#[account(mut)]
@@ -295,6 +600,13 @@ pub struct BurnTokenToSPL<'info> {
 		associated_token::authority = signer,
 	)]
 	pub burn_token_account: InterfaceAccount<'info, TokenAccount>,
+	// Partner front-end the trade came through, see `referral_register`
+	#[account(
+		mut,
+		constraint = referral.mint == mint.key(),
+		constraint = referral.referrer != signer.key() @ Errors::SelfReferral,
+	)]
+	pub referral: Option<Box<Account<'info, ReferralBalance>>>,
 	pub system_program: Program<'info, System>,
 	pub token_program: Program<'info, Token2022>,
 }
@@ -303,23 +615,21 @@ pub struct BurnTokenToSPL<'info> {
 pub struct BurnTokenToSPLArgs {
 	pub amount: u64,
 	pub symbol: String,
-	pub min_receive: u64,
+	pub order: BurnOrder,
 }
 
 pub fn route_burn_token_to_spl(
 	ctx: Context<BurnTokenToSPL>,
 	args: BurnTokenToSPLArgs,
 ) -> Result<()> {
-	// Calculate how many tokens to mint
-	let estimate_res = estimate_burn_amount_from_bonding_curve(
-		ctx.accounts.project_metadata.bonding_curve_type.clone(),
+	let estimate_res = estimate_burn_order(
+		args.order,
 		args.amount,
+		&ctx.accounts.burve_base,
+		&ctx.accounts.project_metadata,
 		ctx.accounts.mint.supply,
-		ctx.accounts.burve_base.mint_tax,
-		ctx.accounts.project_metadata.mint_tax,
-	);
-
-	assert!(estimate_res.actual_received_amount >= args.min_receive, "min_receive not met");
+		TokenDecimals::new(ctx.accounts.raising_token.decimals, ctx.accounts.mint.decimals).map_err(Errors::from)?,
+	)?;
 
 	// Burn tokens
 	burn(
@@ -331,13 +641,13 @@ pub fn route_burn_token_to_spl(
 				mint: ctx.accounts.mint.to_account_info(),
 			},
 		),
-		args.amount
+		estimate_res.burning_amount
 	)?;
 
 
 	// Transfer SPL token from vault
 	let token_program = ctx.accounts.token_program.to_account_info();
-	let accounts = TransferChecked {
+	let cpi_accounts = TransferChecked {
 		from: ctx.accounts.vault.to_account_info().clone(),
 		to: ctx.accounts.to_ata.to_account_info().clone(),
 		authority: ctx.accounts.mint.to_account_info().clone(),
@@ -346,27 +656,30 @@ pub fn route_burn_token_to_spl(
 	let seeds = &[MINT_ACCOUNT_SEED, args.symbol.as_bytes(), &[ctx.bumps.mint]];
 	let signer = [&seeds[..]];
 
-	let cpi_ctx = CpiContext::new_with_signer(token_program, accounts, &signer);
-
-	transfer_checked(cpi_ctx, estimate_res.actual_received_amount, ctx.accounts.mint.decimals)?;
-
-	// Transfer project tax to project treasury
-	let token_program = ctx.accounts.token_program.to_account_info();
-	let accounts = TransferChecked {
-		from: ctx.accounts.vault.to_account_info().clone(),
-		to: ctx.accounts.project_treasury.to_account_info().clone(),
-		authority: ctx.accounts.mint.to_account_info().clone(),
-		mint: ctx.accounts.raising_token.to_account_info().clone(),
-	};
+	let cpi_ctx = CpiContext::new_with_signer(token_program, cpi_accounts, &signer);
 
-	let cpi_ctx = CpiContext::new_with_signer(token_program, accounts, &signer);
+	transfer_checked(cpi_ctx, estimate_res.actual_received_amount, ctx.accounts.raising_token.decimals)?;
 
-	transfer_checked(cpi_ctx, estimate_res.project_fee, ctx.accounts.raising_token.decimals)?;
+	// Project tax stays in the vault until it is claimed
+	accrue_project_fee(&mut ctx.accounts.project_metadata, estimate_res.project_fee)?;
 
 	// Increment burve tax counter
-	ctx.accounts.project_metadata.burve_tax_counter += estimate_res.burve_fee;
+	accrue_burve_fee(
+		&ctx.accounts.burve_base,
+		&mut ctx.accounts.project_metadata,
+		ctx.accounts.referral.as_deref_mut().map(|referral| &mut **referral),
+		estimate_res.burve_fee,
+	)?;
 
-	Ok(())
+	ctx.accounts.mint.reload()?;
+	ctx.accounts.vault.reload()?;
+	check_vault_solvency(
+		ctx.accounts.project_metadata.bonding_curve_type.clone(),
+		ctx.accounts.vault.amount,
+		ctx.accounts.project_metadata.reserved_fees()?,
+		ctx.accounts.mint.supply,
+		TokenDecimals::new(ctx.accounts.raising_token.decimals, ctx.accounts.mint.decimals).map_err(Errors::from)?,
+	)
 }
 
 
@@ -379,9 +692,9 @@ pub struct MintTokenWithSOL<'info> {
 	)]
     pub burve_base: Box<Account<'info, BurveBase>>,
 	#[account(
-		constraint = project_metadata.raising_token == None,
+		mut,
+		constraint = project_metadata.raising_token.is_none(),
 		constraint = project_metadata.symbol == args.symbol,
-		constraint = project_metadata.treasury == project_treasury.key(),
 		seeds = [PROJECT_METADATA_SEED, mint.key().as_ref() ], 
 		bump 
 	)]
@@ -400,10 +713,6 @@ pub struct MintTokenWithSOL<'info> {
 		bump
 	)]
 	pub vault: SystemAccount<'info>,
-	#[account(
-		mut,
-	)]
-	pub project_treasury: SystemAccount<'info>,
 	#[account(
 		init_if_needed,
 		payer = from,
@@ -412,6 +721,13 @@ pub struct MintTokenWithSOL<'info> {
 		associated_token::authority = from,
 	)]
 	pub mint_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+	// Partner front-end the trade came through, see `referral_register`
+	#[account(
+		mut,
+		constraint = referral.mint == mint.key(),
+		constraint = referral.referrer != from.key() @ Errors::SelfReferral,
+	)]
+	pub referral: Option<Box<Account<'info, ReferralBalance>>>,
 	pub associated_token_program: Program<'info, AssociatedToken>,
 	pub token_program: Program<'info, Token2022>,
 	pub system_program: Program<'info, System>,
@@ -421,20 +737,18 @@ pub struct MintTokenWithSOL<'info> {
 pub struct MintTokenWithSOLArgs {
 	pub amount: u64,
 	pub symbol: String,
-	pub min_receive: u64,
+	pub order: MintOrder,
 }
 
 pub fn route_mint_token_with_sol(ctx: Context<MintTokenWithSOL>, args: MintTokenWithSOLArgs) -> Result<()> {
-	// Calculate how many tokens to mint
-	let estimate_res = estimate_mint_amount_from_bonding_curve(
-		ctx.accounts.project_metadata.bonding_curve_type.clone(),
+	let estimate_res = estimate_mint_order(
+		args.order,
 		args.amount,
+		&ctx.accounts.burve_base,
+		&ctx.accounts.project_metadata,
 		ctx.accounts.mint.supply,
-		ctx.accounts.burve_base.mint_tax,
-		ctx.accounts.project_metadata.mint_tax,
-	);
-
-	assert!(estimate_res.calculated_receiving_amount >= args.min_receive, "min_receive not met");
+		TokenDecimals::new(SOL_DECIMALS, ctx.accounts.mint.decimals).map_err(Errors::from)?,
+	)?;
 
 	// Transfer SOL token to vault
 	let cpi_ctx = CpiContext::new(
@@ -444,19 +758,18 @@ pub fn route_mint_token_with_sol(ctx: Context<MintTokenWithSOL>, args: MintToken
 		to: ctx.accounts.vault.to_account_info(),
 	});
 
-	system_program::transfer(cpi_ctx, estimate_res.actual_paid_amount + estimate_res.burve_fee)?;
-
-	ctx.accounts.project_metadata.burve_tax_counter += estimate_res.burve_fee;
+	// Both taxes stay in the vault until they are claimed
+	system_program::transfer(cpi_ctx, estimate_res.paid_amount())?;
 
-	// Transfer project tax to project treasury
-	let cpi_ctx = CpiContext::new(
-	ctx.accounts.system_program.to_account_info(),
-	system_program::Transfer{
-		from: ctx.accounts.from.to_account_info(),
-		to: ctx.accounts.project_treasury.to_account_info(),
-	});
-
-	system_program::transfer(cpi_ctx, estimate_res.project_fee)?;
+	accrue_burve_fee(
+		&ctx.accounts.burve_base,
+		&mut ctx.accounts.project_metadata,
+		ctx.accounts.referral.as_deref_mut().map(|referral| &mut **referral),
+		estimate_res.burve_fee,
+	)?;
+	accrue_project_fee(&mut ctx.accounts.project_metadata, estimate_res.project_fee)?;
+	// The launch premium is the project's too
+	accrue_project_fee(&mut ctx.accounts.project_metadata, estimate_res.launch_premium_fee)?;
 
 	// Mint the project SPL token to mint token account
 	let seeds = &[MINT_ACCOUNT_SEED, args.symbol.as_bytes(), &[ctx.bumps.mint]];
@@ -475,7 +788,21 @@ pub fn route_mint_token_with_sol(ctx: Context<MintTokenWithSOL>, args: MintToken
 		estimate_res.calculated_receiving_amount
 	)?;
 
-	Ok(())
+	ctx.accounts.mint.reload()?;
+	if ctx.accounts.project_metadata.max_supply == Some(ctx.accounts.mint.supply) {
+		emit!(SupplyCapReached {
+			mint: ctx.accounts.mint.key(),
+			max_supply: ctx.accounts.mint.supply,
+		});
+	}
+
+	check_vault_solvency(
+		ctx.accounts.project_metadata.bonding_curve_type.clone(),
+		sol_vault_balance(&ctx.accounts.vault)?,
+		ctx.accounts.project_metadata.reserved_fees()?,
+		ctx.accounts.mint.supply,
+		TokenDecimals::new(SOL_DECIMALS, ctx.accounts.mint.decimals).map_err(Errors::from)?,
+	)
 }
 
 #[derive(Accounts)]
@@ -487,7 +814,8 @@ pub struct BurnTokenToSOL<'info> {
 	)]
 	pub burve_base: Account<'info, BurveBase>,
 	#[account(
-		constraint = project_metadata.raising_token == None,
+		mut,
+		constraint = project_metadata.raising_token.is_none(),
 		constraint = project_metadata.symbol == args.symbol,
 		seeds = [PROJECT_METADATA_SEED, mint.key().as_ref() ], 
 		bump 
@@ -514,10 +842,13 @@ pub struct BurnTokenToSOL<'info> {
 		bump
 	)]
 	pub vault: SystemAccount<'info>,
+	// Partner front-end the trade came through, see `referral_register`
 	#[account(
 		mut,
+		constraint = referral.mint == mint.key(),
+		constraint = referral.referrer != from.key() @ Errors::SelfReferral,
 	)]
-	pub project_treasury: SystemAccount<'info>,
+	pub referral: Option<Box<Account<'info, ReferralBalance>>>,
 	pub token_program: Program<'info, Token2022>,
 	pub system_program: Program<'info, System>,
 }
@@ -526,20 +857,18 @@ pub struct BurnTokenToSOL<'info> {
 pub struct BurnTokenToSOLArgs {
 	pub amount: u64,
 	pub symbol: String,
-	pub min_receive: u64,
+	pub order: BurnOrder,
 }
 
 pub fn route_burn_token_to_sol(ctx: Context<BurnTokenToSOL>, args: BurnTokenToSOLArgs) -> Result<()> {
-	// Calculate how many tokens to burn
-	let estimate_res = estimate_burn_amount_from_bonding_curve(
-		ctx.accounts.project_metadata.bonding_curve_type.clone(),
+	let estimate_res = estimate_burn_order(
+		args.order,
 		args.amount,
+		&ctx.accounts.burve_base,
+		&ctx.accounts.project_metadata,
 		ctx.accounts.mint.supply,
-		ctx.accounts.burve_base.mint_tax,
-		ctx.accounts.project_metadata.mint_tax,
-	);
-
-	assert!(estimate_res.actual_received_amount >= args.min_receive, "min_receive not met");
+		TokenDecimals::new(SOL_DECIMALS, ctx.accounts.mint.decimals).map_err(Errors::from)?,
+	)?;
 
 	// Burn tokens
 	let seeds = &[MINT_ACCOUNT_SEED, args.symbol.as_bytes(), &[ctx.bumps.mint]];
@@ -554,7 +883,7 @@ pub fn route_burn_token_to_sol(ctx: Context<BurnTokenToSOL>, args: BurnTokenToSO
 			},
 			&signer
 		),
-		args.amount
+		estimate_res.burning_amount
 	)?;
 
 	// Transfer SOL token from vault
@@ -571,20 +900,25 @@ pub fn route_burn_token_to_sol(ctx: Context<BurnTokenToSOL>, args: BurnTokenToSO
 
 	system_program::transfer(cpi_ctx, estimate_res.actual_received_amount)?;
 
-	// Transfer project tax to project treasury
-	let cpi_ctx = CpiContext::new_with_signer(
-	ctx.accounts.system_program.to_account_info(),
-	system_program::Transfer{
-		from: ctx.accounts.vault.to_account_info(),
-		to: ctx.accounts.project_treasury.to_account_info(),
-	}, &signer);
-
-	system_program::transfer(cpi_ctx, estimate_res.project_fee)?;
+	// Project tax stays in the vault until it is claimed
+	accrue_project_fee(&mut ctx.accounts.project_metadata, estimate_res.project_fee)?;
 
 	// Increment burve tax counter
-	ctx.accounts.project_metadata.burve_tax_counter += estimate_res.burve_fee;
+	accrue_burve_fee(
+		&ctx.accounts.burve_base,
+		&mut ctx.accounts.project_metadata,
+		ctx.accounts.referral.as_deref_mut().map(|referral| &mut **referral),
+		estimate_res.burve_fee,
+	)?;
 
-	Ok(())
+	ctx.accounts.mint.reload()?;
+	check_vault_solvency(
+		ctx.accounts.project_metadata.bonding_curve_type.clone(),
+		sol_vault_balance(&ctx.accounts.vault)?,
+		ctx.accounts.project_metadata.reserved_fees()?,
+		ctx.accounts.mint.supply,
+		TokenDecimals::new(SOL_DECIMALS, ctx.accounts.mint.decimals).map_err(Errors::from)?,
+	)
 }
 
 #[derive(Accounts)]
@@ -671,7 +1005,7 @@ pub struct ClaimBurveSOLTax<'info> {
 	pub burve_base: Account<'info, BurveBase>,
 	#[account(
 		mut,
-		constraint = project_metadata.raising_token == None,
+		constraint = project_metadata.raising_token.is_none(),
 		constraint = project_metadata.symbol == args.symbol,
 		seeds = [PROJECT_METADATA_SEED, mint.key().as_ref() ], 
 		bump 
@@ -722,3 +1056,686 @@ pub fn route_claim_burve_sol_tax(ctx: Context<ClaimBurveSOLTax>, _args: ClaimBur
 	Ok(())
 }
 
+// Emitted for every project a batch claim took Burve tax from
+#[event]
+pub struct BurveTaxClaimed {
+	pub mint: Pubkey,
+	pub symbol: String,
+	pub amount: u64,
+}
+
+// One (project_metadata, vault, mint) triple of a batch claim, checked to be
+// the project's PDAs
+struct BatchProject<'info> {
+	project_metadata: Account<'info, ProjectMetadata>,
+	vault: &'info AccountInfo<'info>,
+	mint: &'info AccountInfo<'info>,
+	mint_bump: u8,
+	vault_bump: u8,
+}
+
+// A project can only be passed once, as every copy is deserialized before any
+// of them is claimed and would pay out its tax again
+fn batch_projects<'info>(
+	remaining_accounts: &'info [AccountInfo<'info>],
+	program_id: &Pubkey,
+) -> Result<Vec<BatchProject<'info>>> {
+	require!(remaining_accounts.len() % 3 == 0, Errors::ProjectAccountsNotValid);
+	let project_metadata_keys: Vec<Pubkey> = remaining_accounts.iter().step_by(3).map(|account| account.key()).collect();
+	for (i, key) in project_metadata_keys.iter().enumerate() {
+		require!(!project_metadata_keys[..i].contains(key), Errors::ProjectAccountsNotValid);
+	}
+
+	remaining_accounts.chunks(3).map(|accounts| {
+		let project_metadata = Account::<ProjectMetadata>::try_from(&accounts[0])?;
+		let (vault, mint) = (&accounts[1], &accounts[2]);
+
+		let (mint_key, mint_bump) = Pubkey::find_program_address(
+			&[MINT_ACCOUNT_SEED, project_metadata.symbol.as_bytes()],
+			program_id,
+		);
+		require_keys_eq!(mint.key(), mint_key, Errors::ProjectAccountsNotValid);
+		let (project_metadata_key, _) = Pubkey::find_program_address(
+			&[PROJECT_METADATA_SEED, mint_key.as_ref()],
+			program_id,
+		);
+		require_keys_eq!(project_metadata.key(), project_metadata_key, Errors::ProjectAccountsNotValid);
+		let (vault_key, vault_bump) = Pubkey::find_program_address(&[b"vault", mint_key.as_ref()], program_id);
+		require_keys_eq!(vault.key(), vault_key, Errors::ProjectAccountsNotValid);
+
+		Ok(BatchProject { project_metadata, vault, mint, mint_bump, vault_bump })
+	}).collect()
+}
+
+#[derive(Accounts)]
+pub struct ClaimBurveSPLTaxBatch<'info> {
+	#[account(
+		has_one = admin @ Errors::SignerIsNotAdmin,
+		constraint = burve_base.treasury == burve_treasury.owner,
+		seeds = [b"burve"], 
+		bump 
+	)]
+	pub burve_base: Account<'info, BurveBase>,
+	// Every project in the batch raises this token
+	#[account()]
+	pub raising_token: InterfaceAccount<'info, Mint>,
+	pub admin: Signer<'info>,
+	#[account(
+		mut,
+		token::token_program = token_program,
+		token::mint = raising_token,
+	)]
+	pub burve_treasury: InterfaceAccount<'info, TokenAccount>,
+	pub token_program: Program<'info, Token2022>,
+}
+
+// Sweeps the Burve tax of every project passed as a (project_metadata, vault,
+// mint) triple in the remaining accounts
+pub fn route_claim_burve_spl_tax_batch<'info>(
+	ctx: Context<'_, '_, 'info, 'info, ClaimBurveSPLTaxBatch<'info>>,
+) -> Result<()> {
+	for mut project in batch_projects(ctx.remaining_accounts, ctx.program_id)? {
+		require!(
+			project.project_metadata.raising_token == Some(ctx.accounts.raising_token.key()),
+			Errors::ProjectAccountsNotValid
+		);
+		let burve_tax = project.project_metadata.burve_tax_counter;
+		if burve_tax == 0 {
+			continue;
+		}
+
+		// Transfer burve tax to burve treasury
+		let accounts = TransferChecked {
+			from: project.vault.clone(),
+			to: ctx.accounts.burve_treasury.to_account_info().clone(),
+			authority: project.mint.clone(),
+			mint: ctx.accounts.raising_token.to_account_info().clone(),
+		};
+		let seeds = &[MINT_ACCOUNT_SEED, project.project_metadata.symbol.as_bytes(), &[project.mint_bump]];
+		let signer = [&seeds[..]];
+
+		let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), accounts, &signer);
+
+		transfer_checked(cpi_ctx, burve_tax, ctx.accounts.raising_token.decimals)?;
+
+		// Reset burve tax counter
+		project.project_metadata.burve_tax_counter = 0;
+		project.project_metadata.exit(ctx.program_id)?;
+
+		emit!(BurveTaxClaimed {
+			mint: project.mint.key(),
+			symbol: project.project_metadata.symbol.clone(),
+			amount: burve_tax,
+		});
+	}
+	Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimBurveSOLTaxBatch<'info> {
+	#[account(
+		has_one = admin @ Errors::SignerIsNotAdmin,
+		constraint = burve_base.treasury == burve_treasury.key(),
+		seeds = [b"burve"], 
+		bump 
+	)]
+	pub burve_base: Account<'info, BurveBase>,
+	pub admin: Signer<'info>,
+	#[account(mut)]
+	pub burve_treasury: SystemAccount<'info>,
+	pub system_program: Program<'info, System>,
+}
+
+// SOL counterpart of `route_claim_burve_spl_tax_batch`
+pub fn route_claim_burve_sol_tax_batch<'info>(
+	ctx: Context<'_, '_, 'info, 'info, ClaimBurveSOLTaxBatch<'info>>,
+) -> Result<()> {
+	for mut project in batch_projects(ctx.remaining_accounts, ctx.program_id)? {
+		require!(project.project_metadata.raising_token.is_none(), Errors::ProjectAccountsNotValid);
+		let burve_tax = project.project_metadata.burve_tax_counter;
+		if burve_tax == 0 {
+			continue;
+		}
+
+		// Transfer burve tax to burve treasury
+		let seeds = &[b"vault", project.mint.key.as_ref(), &[project.vault_bump]];
+		let signer = [&seeds[..]];
+		let cpi_ctx = CpiContext::new_with_signer(
+		ctx.accounts.system_program.to_account_info(),
+		system_program::Transfer{
+			from: project.vault.clone(),
+			to: ctx.accounts.burve_treasury.to_account_info(),
+		}, &signer);
+
+		system_program::transfer(cpi_ctx, burve_tax)?;
+
+		// Reset burve tax counter
+		project.project_metadata.burve_tax_counter = 0;
+		project.project_metadata.exit(ctx.program_id)?;
+
+		emit!(BurveTaxClaimed {
+			mint: project.mint.key(),
+			symbol: project.project_metadata.symbol.clone(),
+			amount: burve_tax,
+		});
+	}
+	Ok(())
+}
+
+
+#[derive(Accounts)]
+#[instruction(args: ClaimProjectSPLTaxArgs)]
+pub struct ClaimProjectSPLTax<'info> {
+	#[account(
+		mut,
+		has_one = admin @ Errors::SignerIsNotAdmin,
+		constraint = project_metadata.raising_token == Some(raising_token.key()),
+		constraint = project_metadata.symbol == args.symbol,
+		constraint = project_metadata.treasury == project_treasury.key(),
+		seeds = [PROJECT_METADATA_SEED, mint.key().as_ref() ], 
+		bump 
+	)]
+	pub project_metadata: Box<Account<'info, ProjectMetadata>>,
+	#[account(
+		seeds = [MINT_ACCOUNT_SEED, args.symbol.as_bytes()],
+		bump,
+	)]  
+	pub mint: InterfaceAccount<'info, Mint>,
+	#[account()]
+	pub raising_token: InterfaceAccount<'info, Mint>,
+	pub admin: Signer<'info>,
+	#[account(
+		mut,
+		seeds = [b"vault", mint.key().as_ref()],
+		bump
+	)]
+	pub vault: InterfaceAccount<'info, TokenAccount>,
+	#[account(
+		mut,
+		token::mint = raising_token,
+		token::token_program = token_program,
+	)]
+	pub project_treasury: InterfaceAccount<'info, TokenAccount>,
+	pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+pub struct ClaimProjectSPLTaxArgs {
+	pub symbol: String,
+}
+
+// Projects with a revenue split are paid out with
+// `route_distribute_project_spl_tax` instead
+pub fn route_claim_project_spl_tax(ctx: Context<ClaimProjectSPLTax>, args: ClaimProjectSPLTaxArgs) -> Result<()> {
+	require!(ctx.accounts.project_metadata.revenue_split.is_empty(), Errors::ProjectTaxIsSplit);
+	let project_tax = ctx.accounts.project_metadata.project_tax_counter;
+
+	// Transfer project tax to project treasury
+	let token_program = ctx.accounts.token_program.to_account_info();
+	let accounts = TransferChecked {
+		from: ctx.accounts.vault.to_account_info().clone(),
+		to: ctx.accounts.project_treasury.to_account_info().clone(),
+		authority: ctx.accounts.mint.to_account_info().clone(),
+		mint: ctx.accounts.raising_token.to_account_info().clone(),
+	};
+	let seeds = &[MINT_ACCOUNT_SEED, args.symbol.as_bytes(), &[ctx.bumps.mint]];
+	let signer = [&seeds[..]];
+
+	let cpi_ctx = CpiContext::new_with_signer(token_program, accounts, &signer);
+
+	transfer_checked(cpi_ctx, project_tax, ctx.accounts.raising_token.decimals)?;
+
+	// Reset project tax counter
+	ctx.accounts.project_metadata.project_tax_counter = 0;
+
+	Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(args: ClaimProjectSOLTaxArgs)]
+pub struct ClaimProjectSOLTax<'info> {
+	#[account(
+		mut,
+		has_one = admin @ Errors::SignerIsNotAdmin,
+		constraint = project_metadata.raising_token.is_none(),
+		constraint = project_metadata.symbol == args.symbol,
+		constraint = project_metadata.treasury == project_treasury.key(),
+		seeds = [PROJECT_METADATA_SEED, mint.key().as_ref() ], 
+		bump 
+	)]
+	pub project_metadata: Box<Account<'info, ProjectMetadata>>,
+	#[account(
+		seeds = [MINT_ACCOUNT_SEED, args.symbol.as_bytes()],
+		bump,
+	)]  
+	pub mint: InterfaceAccount<'info, Mint>,
+	pub admin: Signer<'info>,
+	#[account(
+		mut,
+		seeds = [b"vault", mint.key().as_ref()],
+		bump
+	)]
+	pub vault: SystemAccount<'info>,
+	#[account(mut)]
+	pub project_treasury: SystemAccount<'info>,
+	pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+pub struct ClaimProjectSOLTaxArgs {
+	pub symbol: String,
+}
+
+// SOL counterpart of `route_claim_project_spl_tax`
+pub fn route_claim_project_sol_tax(ctx: Context<ClaimProjectSOLTax>, _args: ClaimProjectSOLTaxArgs) -> Result<()> {
+	require!(ctx.accounts.project_metadata.revenue_split.is_empty(), Errors::ProjectTaxIsSplit);
+	let project_tax = ctx.accounts.project_metadata.project_tax_counter;
+
+	// Transfer project tax to project treasury
+	let seeds = &[b"vault", ctx.accounts.mint.to_account_info().key.as_ref(), &[ctx.bumps.vault]];
+	let signer = [&seeds[..]];
+	let cpi_ctx = CpiContext::new_with_signer(
+	ctx.accounts.system_program.to_account_info(),
+	system_program::Transfer{
+		from: ctx.accounts.vault.to_account_info(),
+		to: ctx.accounts.project_treasury.to_account_info(),
+	}, &signer);
+
+	system_program::transfer(cpi_ctx, project_tax)?;
+
+	// Reset project tax counter
+	ctx.accounts.project_metadata.project_tax_counter = 0;
+
+	Ok(())
+}
+
+// Pending curve once its timelock is over at `now`
+pub fn due_pending_curve(pending_curve: Option<&PendingCurve>, now: i64) -> Result<BondingCurveType> {
+	let pending_curve = pending_curve.ok_or(error!(Errors::NoPendingCurve))?;
+	require!(now >= pending_curve.effective_time, Errors::CurveTimelockActive);
+	Ok(pending_curve.bonding_curve_type.clone())
+}
+
+// What moves to settle a vault to a new curve's reserve. At most one of
+// `shortfall` and `excess` is non zero.
+#[derive(Debug, PartialEq)]
+pub struct CurveSettlement {
+	pub reserve: u64,
+	// Paid into the vault by the project admin
+	pub shortfall: u64,
+	// Left in the vault as project fees
+	pub excess: u64,
+}
+
+// Settles the vault, less the fees it holds, to the reserve `bonding_curve_type`
+// needs at the current supply
+pub fn settle_vault_to_curve(
+	bonding_curve_type: BondingCurveType,
+	vault_balance: u64,
+	reserved_fees: u64,
+	mint_supply: u64,
+	decimals: TokenDecimals,
+) -> Result<CurveSettlement> {
+	let reserve = required_vault_reserve(bonding_curve_type, mint_supply, decimals)?;
+	let available = vault_balance.saturating_sub(reserved_fees);
+	Ok(CurveSettlement {
+		reserve,
+		shortfall: reserve.saturating_sub(available),
+		excess: available.saturating_sub(reserve),
+	})
+}
+
+#[event]
+pub struct ProjectCurveChanged {
+	pub mint: Pubkey,
+	// Vault reserve the new curve needs at the current supply
+	pub reserve: u64,
+	// Accrued to the project fees held in the vault
+	pub excess: u64,
+	// Paid into the vault by the project admin
+	pub shortfall: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(args: SetProjectCurveWithSPLArgs)]
+pub struct SetProjectCurveWithSPL<'info> {
+    #[account(
+		seeds = [b"burve"], 
+		bump 
+	)]
+    pub burve_base: Box<Account<'info, BurveBase>>,
+	#[account(
+		mut,
+		has_one = admin @ Errors::SignerIsNotAdmin,
+		constraint = project_metadata.raising_token == Some(raising_token.key()),
+		constraint = project_metadata.symbol == args.symbol,
+		seeds = [PROJECT_METADATA_SEED, mint.key().as_ref() ], 
+		bump 
+	)]
+	pub project_metadata: Box<Account<'info, ProjectMetadata>>,
+	#[account(
+		seeds = [MINT_ACCOUNT_SEED, args.symbol.as_bytes()],
+		bump,
+	)]  
+	pub mint: InterfaceAccount<'info, Mint>,
+	#[account()]
+	pub raising_token: Box<InterfaceAccount<'info, Mint>>,
+	#[account(
+		mut,
+		seeds = [b"vault", mint.key().as_ref()],
+		bump,
+		token::mint = raising_token,
+		token::token_program = token_program
+	)]
+	pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+	pub admin: Signer<'info>,
+	#[account(
+		mut,
+		token::mint = raising_token,
+		token::token_program = token_program,
+		token::authority = admin,
+	)]
+	pub admin_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+	pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+pub struct SetProjectCurveWithSPLArgs {
+	pub symbol: String,
+}
+
+// Replaces the curve with the pending one, if Burve still allows its kind. The
+// vault is settled to the new curve's reserve at the current supply: the admin
+// pays in any shortfall and any excess stays in the vault as project fees, paid
+// out like the others by a claim or the revenue split.
+pub fn route_set_project_curve_with_spl(ctx: Context<SetProjectCurveWithSPL>, _args: SetProjectCurveWithSPLArgs) -> Result<()> {
+	let bonding_curve_type = due_pending_curve(ctx.accounts.project_metadata.pending_curve.as_ref(), Clock::get()?.unix_timestamp)?;
+	validate_bonding_curve_type(
+		&ctx.accounts.burve_base,
+		&bonding_curve_type,
+		ctx.accounts.project_metadata.max_supply,
+		ctx.accounts.mint.decimals,
+	)?;
+	let decimals = TokenDecimals::new(ctx.accounts.raising_token.decimals, ctx.accounts.mint.decimals).map_err(Errors::from)?;
+	let CurveSettlement { reserve, shortfall, excess } = settle_vault_to_curve(
+		bonding_curve_type.clone(),
+		ctx.accounts.vault.amount,
+		ctx.accounts.project_metadata.reserved_fees()?,
+		ctx.accounts.mint.supply,
+		decimals,
+	)?;
+
+	if shortfall > 0 {
+		// Transfer shortfall from admin to vault
+		let token_program = ctx.accounts.token_program.to_account_info();
+		let cpi_accounts = TransferChecked {
+			from: ctx.accounts.admin_ata.to_account_info().clone(),
+			to: ctx.accounts.vault.to_account_info().clone(),
+			authority: ctx.accounts.admin.to_account_info().clone(),
+			mint: ctx.accounts.raising_token.to_account_info().clone(),
+		};
+		let cpi_ctx = CpiContext::new(token_program, cpi_accounts);
+
+		transfer_checked(cpi_ctx, shortfall, ctx.accounts.raising_token.decimals)?;
+	}
+	accrue_project_fee(&mut ctx.accounts.project_metadata, excess)?;
+
+	ctx.accounts.project_metadata.bonding_curve_type = bonding_curve_type;
+	ctx.accounts.project_metadata.pending_curve = None;
+
+	emit!(ProjectCurveChanged {
+		mint: ctx.accounts.mint.key(),
+		reserve,
+		excess,
+		shortfall,
+	});
+
+	ctx.accounts.vault.reload()?;
+	check_vault_solvency(
+		ctx.accounts.project_metadata.bonding_curve_type.clone(),
+		ctx.accounts.vault.amount,
+		ctx.accounts.project_metadata.reserved_fees()?,
+		ctx.accounts.mint.supply,
+		decimals,
+	)
+}
+
+#[derive(Accounts)]
+#[instruction(args: SetProjectCurveWithSOLArgs)]
+pub struct SetProjectCurveWithSOL<'info> {
+    #[account(
+		seeds = [b"burve"], 
+		bump 
+	)]
+    pub burve_base: Box<Account<'info, BurveBase>>,
+	#[account(
+		mut,
+		has_one = admin @ Errors::SignerIsNotAdmin,
+		constraint = project_metadata.raising_token.is_none(),
+		constraint = project_metadata.symbol == args.symbol,
+		seeds = [PROJECT_METADATA_SEED, mint.key().as_ref() ], 
+		bump 
+	)]
+	pub project_metadata: Box<Account<'info, ProjectMetadata>>,
+	#[account(
+		seeds = [MINT_ACCOUNT_SEED, args.symbol.as_bytes()],
+		bump,
+	)]  
+	pub mint: InterfaceAccount<'info, Mint>,
+	#[account(
+		mut,
+		seeds = [b"vault", mint.key().as_ref()],
+		bump
+	)]
+	pub vault: SystemAccount<'info>,
+	#[account(mut)]
+	pub admin: Signer<'info>,
+	pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+pub struct SetProjectCurveWithSOLArgs {
+	pub symbol: String,
+}
+
+// SOL counterpart of `route_set_project_curve_with_spl`
+pub fn route_set_project_curve_with_sol(ctx: Context<SetProjectCurveWithSOL>, _args: SetProjectCurveWithSOLArgs) -> Result<()> {
+	let bonding_curve_type = due_pending_curve(ctx.accounts.project_metadata.pending_curve.as_ref(), Clock::get()?.unix_timestamp)?;
+	validate_bonding_curve_type(
+		&ctx.accounts.burve_base,
+		&bonding_curve_type,
+		ctx.accounts.project_metadata.max_supply,
+		ctx.accounts.mint.decimals,
+	)?;
+	let decimals = TokenDecimals::new(SOL_DECIMALS, ctx.accounts.mint.decimals).map_err(Errors::from)?;
+	let CurveSettlement { reserve, shortfall, excess } = settle_vault_to_curve(
+		bonding_curve_type.clone(),
+		sol_vault_balance(&ctx.accounts.vault)?,
+		ctx.accounts.project_metadata.reserved_fees()?,
+		ctx.accounts.mint.supply,
+		decimals,
+	)?;
+
+	if shortfall > 0 {
+		// Transfer shortfall from admin to vault
+		let cpi_ctx = CpiContext::new(
+		ctx.accounts.system_program.to_account_info(),
+		system_program::Transfer{
+			from: ctx.accounts.admin.to_account_info(),
+			to: ctx.accounts.vault.to_account_info(),
+		});
+
+		system_program::transfer(cpi_ctx, shortfall)?;
+	}
+	accrue_project_fee(&mut ctx.accounts.project_metadata, excess)?;
+
+	ctx.accounts.project_metadata.bonding_curve_type = bonding_curve_type;
+	ctx.accounts.project_metadata.pending_curve = None;
+
+	emit!(ProjectCurveChanged {
+		mint: ctx.accounts.mint.key(),
+		reserve,
+		excess,
+		shortfall,
+	});
+
+	check_vault_solvency(
+		ctx.accounts.project_metadata.bonding_curve_type.clone(),
+		sol_vault_balance(&ctx.accounts.vault)?,
+		ctx.accounts.project_metadata.reserved_fees()?,
+		ctx.accounts.mint.supply,
+		decimals,
+	)
+}
+
+// Emitted for every distribution of a project's split fees
+#[event]
+pub struct ProjectTaxDistributed {
+	pub mint: Pubkey,
+	pub amount: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(args: DistributeProjectTaxArgs)]
+pub struct DistributeProjectSPLTax<'info> {
+	#[account(
+		mut,
+		constraint = project_metadata.raising_token == Some(raising_token.key()),
+		constraint = project_metadata.symbol == args.symbol,
+		seeds = [PROJECT_METADATA_SEED, mint.key().as_ref() ], 
+		bump 
+	)]
+	pub project_metadata: Box<Account<'info, ProjectMetadata>>,
+	#[account(
+		seeds = [MINT_ACCOUNT_SEED, args.symbol.as_bytes()],
+		bump,
+	)]  
+	pub mint: InterfaceAccount<'info, Mint>,
+	#[account()]
+	pub raising_token: InterfaceAccount<'info, Mint>,
+	#[account(
+		mut,
+		seeds = [b"vault", mint.key().as_ref()],
+		bump
+	)]
+	pub vault: InterfaceAccount<'info, TokenAccount>,
+	pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+pub struct DistributeProjectTaxArgs {
+	pub symbol: String,
+}
+
+// Pays the accrued project fees out by the revenue split weights. Anyone can
+// crank it: the recipients' token accounts are passed as remaining accounts,
+// in split order, and checked against the split.
+pub fn route_distribute_project_spl_tax<'info>(
+	ctx: Context<'_, '_, '_, 'info, DistributeProjectSPLTax<'info>>,
+	args: DistributeProjectTaxArgs,
+) -> Result<()> {
+	let project_tax = ctx.accounts.project_metadata.project_tax_counter;
+	let shares = revenue_shares(&ctx.accounts.project_metadata, ctx.remaining_accounts, project_tax)?;
+
+	// Transfer each share to its recipient
+	let seeds = &[MINT_ACCOUNT_SEED, args.symbol.as_bytes(), &[ctx.bumps.mint]];
+	let signer = [&seeds[..]];
+	for (recipient, share) in ctx.remaining_accounts.iter().zip(shares) {
+		if share == 0 {
+			continue;
+		}
+		let cpi_accounts = TransferChecked {
+			from: ctx.accounts.vault.to_account_info().clone(),
+			to: recipient.clone(),
+			authority: ctx.accounts.mint.to_account_info().clone(),
+			mint: ctx.accounts.raising_token.to_account_info().clone(),
+		};
+		let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, &signer);
+
+		transfer_checked(cpi_ctx, share, ctx.accounts.raising_token.decimals)?;
+	}
+
+	// Reset project tax counter
+	ctx.accounts.project_metadata.project_tax_counter = 0;
+
+	emit!(ProjectTaxDistributed {
+		mint: ctx.accounts.mint.key(),
+		amount: project_tax,
+	});
+	Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(args: DistributeProjectTaxArgs)]
+pub struct DistributeProjectSOLTax<'info> {
+	#[account(
+		mut,
+		constraint = project_metadata.raising_token.is_none(),
+		constraint = project_metadata.symbol == args.symbol,
+		seeds = [PROJECT_METADATA_SEED, mint.key().as_ref() ], 
+		bump 
+	)]
+	pub project_metadata: Box<Account<'info, ProjectMetadata>>,
+	#[account(
+		seeds = [MINT_ACCOUNT_SEED, args.symbol.as_bytes()],
+		bump,
+	)]  
+	pub mint: InterfaceAccount<'info, Mint>,
+	#[account(
+		mut,
+		seeds = [b"vault", mint.key().as_ref()],
+		bump
+	)]
+	pub vault: SystemAccount<'info>,
+	pub system_program: Program<'info, System>,
+}
+
+// SOL counterpart of `route_distribute_project_spl_tax`, the recipients are
+// wallets
+pub fn route_distribute_project_sol_tax<'info>(
+	ctx: Context<'_, '_, '_, 'info, DistributeProjectSOLTax<'info>>,
+	_args: DistributeProjectTaxArgs,
+) -> Result<()> {
+	let project_tax = ctx.accounts.project_metadata.project_tax_counter;
+	let shares = revenue_shares(&ctx.accounts.project_metadata, ctx.remaining_accounts, project_tax)?;
+
+	// Transfer each share to its recipient
+	let seeds = &[b"vault", ctx.accounts.mint.to_account_info().key.as_ref(), &[ctx.bumps.vault]];
+	let signer = [&seeds[..]];
+	for (recipient, share) in ctx.remaining_accounts.iter().zip(shares) {
+		if share == 0 {
+			continue;
+		}
+		let cpi_ctx = CpiContext::new_with_signer(
+		ctx.accounts.system_program.to_account_info(),
+		system_program::Transfer{
+			from: ctx.accounts.vault.to_account_info(),
+			to: recipient.clone(),
+		}, &signer);
+
+		system_program::transfer(cpi_ctx, share)?;
+	}
+
+	// Reset project tax counter
+	ctx.accounts.project_metadata.project_tax_counter = 0;
+
+	emit!(ProjectTaxDistributed {
+		mint: ctx.accounts.mint.key(),
+		amount: project_tax,
+	});
+	Ok(())
+}
+
+// Each recipient's share of `amount`, once the passed accounts are checked to
+// be the split's recipients in order
+fn revenue_shares(project_metadata: &ProjectMetadata, recipients: &[AccountInfo], amount: u64) -> Result<Vec<u64>> {
+	let split = &project_metadata.revenue_split;
+	require!(!split.is_empty(), Errors::RevenueSplitNotValid);
+	require!(recipients.len() == split.len(), Errors::RecipientNotValid);
+	for (account, recipient) in recipients.iter().zip(split) {
+		require_keys_eq!(account.key(), recipient.recipient, Errors::RecipientNotValid);
+	}
+
+	let weights: Vec<u16> = split.iter().map(|recipient| recipient.weight).collect();
+	Ok(split_by_weights(amount, &weights).map_err(Errors::from)?)
+}
@@ -1,6 +1,6 @@
 use std::mem::size_of;
 
-use anchor_lang::{prelude::*, solana_program::entrypoint::ProgramResult};
+use anchor_lang::{prelude::*, solana_program::entrypoint::ProgramResult, Discriminator};
 
 use anchor_spl::
     token_interface::{
@@ -9,7 +9,7 @@ use anchor_spl::
 };
 
 
-use crate::Errors;
+use crate::{curve_kind, validate_curve, validate_launch_auction, validate_split_weights, BurveBase, Errors, StepTiers, MAX_DECIMALS, WAD};
 
 use crate::{
     update_account_lamports_to_minimum_balance,  MINT_ACCOUNT_SEED,
@@ -18,11 +18,110 @@ use crate::{
 
 const MAX_PLATFORM_TAX_RATE: u16 = 5000;
 
+// Curves must still price this many whole tokens, in WAD
+pub const DEFAULT_MAX_SUPPLY: u128 = 1_000_000_000 * WAD;
+
+// ProjectMetadata is allocated once at creation, so the tier list is bounded,
+// once for the live curve and once for a pending one, and so is the revenue
+// split
+pub const MAX_STEPPED_TIERS: usize = 8;
+
+#[derive(AnchorDeserialize, AnchorSerialize, Debug, Clone, PartialEq)]
+pub struct StepTier {
+	pub supply_breakpoint: u128,
+	pub price: u128,
+}
+
+// The curve math works on plain (supply_breakpoint, price) pairs
+pub fn stepped_tiers(tiers: Vec<StepTier>) -> StepTiers {
+	tiers.into_iter().map(|tier| (tier.supply_breakpoint, tier.price)).collect()
+}
+
+
+// Project fees can be split between this many recipients
+pub const MAX_REVENUE_RECIPIENTS: usize = 4;
+
+// Receives `weight` out of MAX_TAX_RATE_DENOMINATOR of the project fee: a
+// token account of the raising token for SPL projects, a wallet for SOL ones
+#[derive(AnchorDeserialize, AnchorSerialize, Debug, Clone, PartialEq)]
+pub struct RevenueRecipient {
+	pub recipient: Pubkey,
+	pub weight: u16,
+}
+
+// Holders get this long to exit before a new curve can replace the live one
+pub const CURVE_CHANGE_TIMELOCK: i64 = 3 * 24 * 60 * 60;
+
+// Curve proposed by the project admin, see `factory_propose_project_curve`
+#[derive(AnchorDeserialize, AnchorSerialize, Debug, Clone, PartialEq)]
+pub struct PendingCurve {
+	pub bonding_curve_type: BondingCurveType,
+	// Earliest time the curve can be set
+	pub effective_time: i64,
+}
+
+// Premium on the curve's price while a launch auction runs, see
+// `launch_premium`
+#[derive(AnchorDeserialize, AnchorSerialize, Debug, Clone, PartialEq)]
+pub struct LaunchAuction {
+	// Multiplier on the price at launch, in WAD
+	pub start_multiplier: u128,
+	pub start_time: i64,
+	// Seconds for the premium to decay away
+	pub duration: i64,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Debug, Clone, PartialEq)]
+pub struct LaunchAuctionArgs {
+	pub start_multiplier: u128,
+	pub duration: i64,
+}
 
 #[derive(AnchorDeserialize, AnchorSerialize, Debug, Clone, PartialEq)]
 pub enum BondingCurveType {
-	Linear{a:u64, b:u64},
-	Exponential{a:u64, b:u64},
+	Linear{a:u128, b:u128},
+	Exponential{a:u128, b:u128},
+	Power{a:u128, b:u128},
+	ConstantProduct{virtual_token_reserve:u128, virtual_raising_reserve:u128},
+	Stepped{tiers:Vec<StepTier>},
+	Sigmoid{max_price:u128, midpoint_supply:u128, steepness:u128},
+}
+
+// The curve kind has to be enabled in BurveBase, and the curve has to price
+// tokens up to the project's max supply, or up to DEFAULT_MAX_SUPPLY for
+// projects without one
+pub fn validate_bonding_curve_type(
+	burve_base: &BurveBase,
+	bonding_curve_type: &BondingCurveType,
+	max_supply: Option<u64>,
+	project_decimals: u8,
+) -> Result<()> {
+	require!(burve_base.curve_enabled(curve_kind(bonding_curve_type)), Errors::CurveNotEnabled);
+	require!(project_decimals <= MAX_DECIMALS, Errors::DecimalsNotValid);
+	let max_supply = match max_supply {
+		Some(max_supply) => {
+			require!(max_supply > 0, Errors::InvalidCurveParameters);
+			// Normalised to WAD like every amount the curves see
+			max_supply as u128 * 10u128.pow((MAX_DECIMALS - project_decimals) as u32)
+		}
+		None => DEFAULT_MAX_SUPPLY,
+	};
+	validate_curve(bonding_curve_type, max_supply).map_err(Errors::from)?;
+	Ok(())
+}
+
+fn launch_auction_from_args(args: Option<LaunchAuctionArgs>) -> Result<Option<LaunchAuction>> {
+	match args {
+		Some(args) => {
+			validate_launch_auction(args.start_multiplier, args.duration).map_err(Errors::from)?;
+			Ok(Some(LaunchAuction {
+				start_multiplier: args.start_multiplier,
+				start_time: Clock::get()?.unix_timestamp,
+				duration: args.duration,
+			}))
+		}
+		None => Ok(None),
+	}
 }
 
 #[account]
@@ -35,6 +134,36 @@ pub struct ProjectMetadata {
 	pub raising_token: Option<Pubkey>,
 	pub bonding_curve_type: BondingCurveType,
 	pub burve_tax_counter: u64,
+	// Mints stop once the supply reaches it, in project token units
+	pub max_supply: Option<u64>,
+	// Starts when the project is created
+	pub launch_auction: Option<LaunchAuction>,
+	pub pending_curve: Option<PendingCurve>,
+	// Share of the Burve fee paid to referrers, overriding BurveBase's
+	pub referral_share: Option<u16>,
+	// Referral fees held in the vault until referrers claim them
+	pub referral_fee_counter: u64,
+	// Empty leaves the whole project fee to the treasury
+	pub revenue_split: Vec<RevenueRecipient>,
+	// Project fees and launch premiums held in the vault until they are
+	// claimed to the treasury, or distributed to the revenue split
+	pub project_tax_counter: u64,
+}
+
+// Room for the tiers of a live and a pending stepped curve, and for a full
+// revenue split
+pub const PROJECT_METADATA_SPACE: usize = size_of::<ProjectMetadata>() + 8
+	+ 2 * MAX_STEPPED_TIERS * size_of::<StepTier>()
+	+ MAX_REVENUE_RECIPIENTS * size_of::<RevenueRecipient>();
+
+impl ProjectMetadata {
+	// Fees held in the vault on top of the curve's reserve
+	pub fn reserved_fees(&self) -> Result<u64> {
+		self.burve_tax_counter
+			.checked_add(self.referral_fee_counter)
+			.and_then(|fees| fees.checked_add(self.project_tax_counter))
+			.ok_or(error!(Errors::CounterOverflow))
+	}
 }
 
 #[derive(AnchorDeserialize, AnchorSerialize)]
@@ -43,21 +172,29 @@ pub struct CreateProjectArgs {
     pub name: String,
     pub symbol: String,
     pub uri: String,
+    pub decimals: u8,
 	// Project related metadata
 	pub admin: Pubkey,
 	pub treasury: Pubkey,
 	pub mint_tax: u16,
 	pub burn_tax: u16,
 	pub bonding_curve_type: BondingCurveType,
+	pub max_supply: Option<u64>,
+	pub launch_auction: Option<LaunchAuctionArgs>,
 }
 
 #[derive(Accounts)]
 #[instruction(args: CreateProjectArgs)]
 pub struct CreateProjectWithSPL<'info> {
+	#[account(
+		seeds = [b"burve"], 
+		bump 
+	)]
+	pub burve_base: Box<Account<'info, BurveBase>>,
     #[account(
 		init, 
 		payer = payer, 
-		space = size_of::<ProjectMetadata>() + 8, 
+		space = PROJECT_METADATA_SPACE, 
 		seeds = [PROJECT_METADATA_SEED, mint.key().as_ref() ], 
 		bump 
 	)]
@@ -88,7 +225,7 @@ pub struct CreateProjectWithSPL<'info> {
 		seeds = [MINT_ACCOUNT_SEED, args.symbol.as_bytes()],
 		bump,
         mint::token_program = token_program,
-        mint::decimals = 9,
+        mint::decimals = args.decimals,
         mint::authority = mint,
         mint::freeze_authority = mint,
         extensions::metadata_pointer::authority = mint,
@@ -136,6 +273,14 @@ pub fn factory_create_project_with_spl(
 
 	require!(args.mint_tax <= MAX_PLATFORM_TAX_RATE, Errors::TaxRateNotValid);
 	require!(args.burn_tax <= MAX_PLATFORM_TAX_RATE, Errors::TaxRateNotValid);
+	require!(args.decimals <= MAX_DECIMALS, Errors::DecimalsNotValid);
+	require!(ctx.accounts.raising_token.decimals <= MAX_DECIMALS, Errors::DecimalsNotValid);
+	validate_bonding_curve_type(
+		&ctx.accounts.burve_base,
+		&args.bonding_curve_type,
+		args.max_supply,
+		args.decimals,
+	)?;
 
 	ctx.accounts.project_metadata.admin = args.admin;
 	ctx.accounts.project_metadata.treasury = ctx.accounts.project_treasury.key();
@@ -145,6 +290,13 @@ pub fn factory_create_project_with_spl(
 	ctx.accounts.project_metadata.raising_token = Some(ctx.accounts.raising_token.key());
 	ctx.accounts.project_metadata.bonding_curve_type = args.bonding_curve_type;
 	ctx.accounts.project_metadata.burve_tax_counter = 0;
+	ctx.accounts.project_metadata.max_supply = args.max_supply;
+	ctx.accounts.project_metadata.launch_auction = launch_auction_from_args(args.launch_auction)?;
+	ctx.accounts.project_metadata.pending_curve = None;
+	ctx.accounts.project_metadata.referral_share = None;
+	ctx.accounts.project_metadata.referral_fee_counter = 0;
+	ctx.accounts.project_metadata.revenue_split = vec![];
+	ctx.accounts.project_metadata.project_tax_counter = 0;
 
     ctx.accounts.initialize_token_metadata(
         args.name.clone(),
@@ -177,10 +329,15 @@ pub fn factory_create_project_with_spl(
 #[derive(Accounts)]
 #[instruction(args: CreateProjectArgs)]
 pub struct CreateProjectWithSOL<'info> {
+	#[account(
+		seeds = [b"burve"], 
+		bump 
+	)]
+	pub burve_base: Box<Account<'info, BurveBase>>,
     #[account(
 		init, 
 		payer = payer, 
-		space = size_of::<ProjectMetadata>() + 8, 
+		space = PROJECT_METADATA_SPACE, 
 		seeds = [PROJECT_METADATA_SEED, mint.key().as_ref() ], 
 		bump 
 	)]
@@ -200,7 +357,7 @@ pub struct CreateProjectWithSOL<'info> {
 		seeds = [MINT_ACCOUNT_SEED, args.symbol.as_bytes()],
 		bump,
         mint::token_program = token_program,
-        mint::decimals = 9,
+        mint::decimals = args.decimals,
         mint::authority = mint,
         mint::freeze_authority = mint,
         extensions::metadata_pointer::authority = mint,
@@ -247,6 +404,13 @@ pub fn factory_create_project_with_sol(
 
 	require!(args.mint_tax <= MAX_PLATFORM_TAX_RATE, Errors::TaxRateNotValid);
 	require!(args.burn_tax <= MAX_PLATFORM_TAX_RATE, Errors::TaxRateNotValid);
+	require!(args.decimals <= MAX_DECIMALS, Errors::DecimalsNotValid);
+	validate_bonding_curve_type(
+		&ctx.accounts.burve_base,
+		&args.bonding_curve_type,
+		args.max_supply,
+		args.decimals,
+	)?;
 
 	ctx.accounts.project_metadata.admin = args.admin;
 	ctx.accounts.project_metadata.treasury = args.treasury;
@@ -256,6 +420,13 @@ pub fn factory_create_project_with_sol(
 	ctx.accounts.project_metadata.raising_token = None;
 	ctx.accounts.project_metadata.bonding_curve_type = args.bonding_curve_type;
 	ctx.accounts.project_metadata.burve_tax_counter = 0;
+	ctx.accounts.project_metadata.max_supply = args.max_supply;
+	ctx.accounts.project_metadata.launch_auction = launch_auction_from_args(args.launch_auction)?;
+	ctx.accounts.project_metadata.pending_curve = None;
+	ctx.accounts.project_metadata.referral_share = None;
+	ctx.accounts.project_metadata.referral_fee_counter = 0;
+	ctx.accounts.project_metadata.revenue_split = vec![];
+	ctx.accounts.project_metadata.project_tax_counter = 0;
 
     ctx.accounts.initialize_token_metadata(
         args.name.clone(),
@@ -285,6 +456,87 @@ pub fn factory_create_project_with_sol(
     Ok(())
 }
 
+// ProjectMetadata as stored before referrals, which added `referral_share` and
+// `referral_fee_counter`, and before the revenue split and project fee counter
+#[derive(AnchorDeserialize)]
+struct LegacyProjectMetadata {
+	admin: Pubkey,
+	treasury: Pubkey,
+	symbol: String,
+	mint_tax: u16,
+	burn_tax: u16,
+	raising_token: Option<Pubkey>,
+	bonding_curve_type: BondingCurveType,
+	burve_tax_counter: u64,
+	max_supply: Option<u64>,
+	launch_auction: Option<LaunchAuction>,
+	pending_curve: Option<PendingCurve>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateProjectMetadata<'info> {
+	#[account(
+		has_one = admin @ Errors::SignerIsNotAdmin,
+		seeds = [b"burve"], 
+		bump 
+	)]
+	pub burve_base: Box<Account<'info, BurveBase>>,
+	/// CHECK: still in the legacy layout, so it is read by hand
+	#[account(
+		mut,
+		owner = crate::ID,
+		seeds = [PROJECT_METADATA_SEED, mint.key().as_ref() ], 
+		bump 
+	)]
+	pub project_metadata: UncheckedAccount<'info>,
+	pub mint: InterfaceAccount<'info, Mint>,
+	#[account(mut)]
+	pub admin: Signer<'info>,
+	pub system_program: Program<'info, System>,
+}
+
+// Grows a ProjectMetadata created before referrals to the current layout, for
+// the Burve admin to run over every project once the program is upgraded.
+// Project fees went straight to the treasury back then, so nothing is pending.
+pub fn factory_migrate_project_metadata(ctx: Context<MigrateProjectMetadata>) -> Result<()> {
+	let project_metadata = ctx.accounts.project_metadata.to_account_info();
+	require!(project_metadata.data_len() < PROJECT_METADATA_SPACE, Errors::AccountAlreadyMigrated);
+
+	let legacy = {
+		let data = project_metadata.try_borrow_data()?;
+		require!(data.starts_with(&ProjectMetadata::DISCRIMINATOR), ErrorCode::AccountDiscriminatorMismatch);
+		LegacyProjectMetadata::deserialize(&mut &data[8..])
+			.map_err(|_| error!(ErrorCode::AccountDidNotDeserialize))?
+	};
+
+	project_metadata.realloc(PROJECT_METADATA_SPACE, true)?;
+	update_account_lamports_to_minimum_balance(
+		project_metadata.clone(),
+		ctx.accounts.admin.to_account_info(),
+		ctx.accounts.system_program.to_account_info(),
+	)?;
+
+	let migrated = ProjectMetadata {
+		admin: legacy.admin,
+		treasury: legacy.treasury,
+		symbol: legacy.symbol,
+		mint_tax: legacy.mint_tax,
+		burn_tax: legacy.burn_tax,
+		raising_token: legacy.raising_token,
+		bonding_curve_type: legacy.bonding_curve_type,
+		burve_tax_counter: legacy.burve_tax_counter,
+		max_supply: legacy.max_supply,
+		launch_auction: legacy.launch_auction,
+		pending_curve: legacy.pending_curve,
+		referral_share: None,
+		referral_fee_counter: 0,
+		revenue_split: Vec::new(),
+		project_tax_counter: 0,
+	};
+	let mut data = project_metadata.try_borrow_mut_data()?;
+	migrated.try_serialize(&mut &mut data[..])
+}
+
 #[derive(Accounts)]
 pub struct SetProjectAdmin<'info> {
 	#[account(mut, has_one = admin @ Errors::SignerIsNotAdmin)]
@@ -304,7 +556,10 @@ pub struct SetProjectTreasury<'info> {
 	pub admin: Signer<'info>,
 }
 
+// The treasury is locked while a curve change is pending, so the destination
+// of the project fees is known for the whole timelock
 pub fn factory_set_project_treasury(ctx: Context<SetProjectTreasury>, new_treasury: Pubkey) -> Result<()> {
+	require!(ctx.accounts.project_metadata.pending_curve.is_none(), Errors::CurveChangePending);
 	ctx.accounts.project_metadata.treasury = new_treasury;
 	Ok(())
 }
@@ -329,3 +584,118 @@ pub fn factory_set_project_tax(ctx: Context<SetProjectTax>, args: SetProjectTaxA
 	ctx.accounts.project_metadata.burn_tax = args.new_burn_tax;
 	Ok(())
 }
+
+#[derive(Accounts)]
+pub struct SetProjectRevenueSplit<'info> {
+	#[account(mut, has_one = admin @ Errors::SignerIsNotAdmin)]
+	pub project_metadata: Account<'info, ProjectMetadata>,
+	pub admin: Signer<'info>,
+}
+
+// Replaces the split, or clears it with an empty list. The recipients' accounts
+// are passed as remaining accounts, in split order, and checked to be able to
+// take their share. Project fees accrued so far go by whichever split is set
+// when they are claimed or distributed, so a broken split can be replaced
+// while fees are pending.
+pub fn factory_set_project_revenue_split<'info>(
+	ctx: Context<'_, '_, 'info, 'info, SetProjectRevenueSplit<'info>>,
+	recipients: Vec<RevenueRecipient>,
+) -> Result<()> {
+	if !recipients.is_empty() {
+		let weights: Vec<u16> = recipients.iter().map(|recipient| recipient.weight).collect();
+		validate_split_weights(&weights).map_err(|_| error!(Errors::RevenueSplitNotValid))?;
+		for (i, recipient) in recipients.iter().enumerate() {
+			require!(
+				recipients[..i].iter().all(|other| other.recipient != recipient.recipient),
+				Errors::RevenueSplitNotValid
+			);
+		}
+	}
+
+	require!(ctx.remaining_accounts.len() == recipients.len(), Errors::RecipientNotValid);
+	for (account, recipient) in ctx.remaining_accounts.iter().zip(&recipients) {
+		require_keys_eq!(account.key(), recipient.recipient, Errors::RecipientNotValid);
+		validate_revenue_recipient(&ctx.accounts.project_metadata, account)?;
+	}
+
+	ctx.accounts.project_metadata.revenue_split = recipients;
+	Ok(())
+}
+
+// A Token-2022 account of the raising token for SPL projects, a rent exempt
+// wallet for SOL ones, so distributing to it can't fail
+fn validate_revenue_recipient<'info>(project_metadata: &ProjectMetadata, account: &'info AccountInfo<'info>) -> Result<()> {
+	match project_metadata.raising_token {
+		Some(raising_token) => {
+			require_keys_eq!(*account.owner, Token2022::id(), Errors::RecipientNotValid);
+			let token_account = InterfaceAccount::<TokenAccount>::try_from(account)
+				.map_err(|_| error!(Errors::RecipientNotValid))?;
+			require_keys_eq!(token_account.mint, raising_token, Errors::RecipientNotValid);
+		}
+		None => {
+			require_keys_eq!(*account.owner, System::id(), Errors::RecipientNotValid);
+			require!(
+				account.data_is_empty() && account.lamports() >= Rent::get()?.minimum_balance(0),
+				Errors::RecipientNotValid
+			);
+		}
+	}
+	Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ProposeProjectCurve<'info> {
+	#[account(
+		seeds = [b"burve"], 
+		bump 
+	)]
+	pub burve_base: Box<Account<'info, BurveBase>>,
+	#[account(
+		mut,
+		has_one = admin @ Errors::SignerIsNotAdmin,
+		seeds = [PROJECT_METADATA_SEED, mint.key().as_ref() ],
+		bump
+	)]
+	pub project_metadata: Account<'info, ProjectMetadata>,
+	pub mint: InterfaceAccount<'info, Mint>,
+	pub admin: Signer<'info>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+pub struct ProposeProjectCurveArgs {
+	pub bonding_curve_type: BondingCurveType,
+}
+
+// Announced so holders can exit before the curve changes
+#[event]
+pub struct ProjectCurveProposed {
+	pub mint: Pubkey,
+	pub bonding_curve_type: BondingCurveType,
+	pub effective_time: i64,
+}
+
+// Queues a new curve, replacing any pending one, which the admin can set with
+// `route_set_project_curve_with_*` once CURVE_CHANGE_TIMELOCK has passed
+pub fn factory_propose_project_curve(ctx: Context<ProposeProjectCurve>, args: ProposeProjectCurveArgs) -> Result<()> {
+	validate_bonding_curve_type(
+		&ctx.accounts.burve_base,
+		&args.bonding_curve_type,
+		ctx.accounts.project_metadata.max_supply,
+		ctx.accounts.mint.decimals,
+	)?;
+
+	let effective_time = Clock::get()?.unix_timestamp
+		.checked_add(CURVE_CHANGE_TIMELOCK)
+		.ok_or(error!(Errors::CurveOverflow))?;
+	ctx.accounts.project_metadata.pending_curve = Some(PendingCurve {
+		bonding_curve_type: args.bonding_curve_type.clone(),
+		effective_time,
+	});
+
+	emit!(ProjectCurveProposed {
+		mint: ctx.accounts.mint.key(),
+		bonding_curve_type: args.bonding_curve_type,
+		effective_time,
+	});
+	Ok(())
+}
@@ -26,6 +26,31 @@ pub mod burve_solana_contract {
         burve_set_tax(ctx, args)
     }
 
+    pub fn migrate_burve_base(ctx: Context<MigrateBurveBase>) -> Result<()> {
+        burve_migrate(ctx)
+    }
+
+    pub fn set_burve_curve_enabled(
+        ctx: Context<SetBurveCurveEnabled>,
+        args: SetBurveCurveEnabledArgs,
+    ) -> Result<()> {
+        burve_set_curve_enabled(ctx, args)
+    }
+
+    pub fn set_burve_referral_share(
+        ctx: Context<SetBurveReferralShare>,
+        new_referral_share: u16,
+    ) -> Result<()> {
+        burve_set_referral_share(ctx, new_referral_share)
+    }
+
+    pub fn set_project_referral_share(
+        ctx: Context<SetProjectReferralShare>,
+        new_referral_share: Option<u16>,
+    ) -> Result<()> {
+        burve_set_project_referral_share(ctx, new_referral_share)
+    }
+
     pub fn create_new_project_with_spl(
         ctx: Context<CreateProjectWithSPL>,
         args: CreateProjectArgs,
@@ -40,6 +65,10 @@ pub mod burve_solana_contract {
         factory_create_project_with_sol(ctx, args)
     }
 
+    pub fn migrate_project_metadata(ctx: Context<MigrateProjectMetadata>) -> Result<()> {
+        factory_migrate_project_metadata(ctx)
+    }
+
     pub fn set_project_admin(ctx: Context<SetProjectAdmin>, new_admin: Pubkey) -> Result<()> {
         factory_set_project_admin(ctx, new_admin)
     }
@@ -55,6 +84,34 @@ pub mod burve_solana_contract {
         factory_set_project_treasury(ctx, new_treasury)
     }
 
+    pub fn set_project_revenue_split<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SetProjectRevenueSplit<'info>>,
+        recipients: Vec<RevenueRecipient>,
+    ) -> Result<()> {
+        factory_set_project_revenue_split(ctx, recipients)
+    }
+
+    pub fn propose_project_curve(
+        ctx: Context<ProposeProjectCurve>,
+        args: ProposeProjectCurveArgs,
+    ) -> Result<()> {
+        factory_propose_project_curve(ctx, args)
+    }
+
+    pub fn set_project_curve_with_spl(
+        ctx: Context<SetProjectCurveWithSPL>,
+        args: SetProjectCurveWithSPLArgs,
+    ) -> Result<()> {
+        route_set_project_curve_with_spl(ctx, args)
+    }
+
+    pub fn set_project_curve_with_sol(
+        ctx: Context<SetProjectCurveWithSOL>,
+        args: SetProjectCurveWithSOLArgs,
+    ) -> Result<()> {
+        route_set_project_curve_with_sol(ctx, args)
+    }
+
     pub fn mint_token_with_spl(
         ctx: Context<MintTokenWithSPL>,
         args: MintTokenWithSPLArgs,
@@ -90,4 +147,62 @@ pub mod burve_solana_contract {
     ) -> Result<()> {
         route_claim_burve_sol_tax(ctx, args)
     }
+
+    pub fn claim_burve_spl_tax_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimBurveSPLTaxBatch<'info>>,
+    ) -> Result<()> {
+        route_claim_burve_spl_tax_batch(ctx)
+    }
+
+    pub fn claim_burve_sol_tax_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimBurveSOLTaxBatch<'info>>,
+    ) -> Result<()> {
+        route_claim_burve_sol_tax_batch(ctx)
+    }
+
+    pub fn claim_project_spl_tax(
+        ctx: Context<ClaimProjectSPLTax>,
+        args: ClaimProjectSPLTaxArgs,
+    ) -> Result<()> {
+        route_claim_project_spl_tax(ctx, args)
+    }
+
+    pub fn claim_project_sol_tax(
+        ctx: Context<ClaimProjectSOLTax>,
+        args: ClaimProjectSOLTaxArgs,
+    ) -> Result<()> {
+        route_claim_project_sol_tax(ctx, args)
+    }
+
+    pub fn distribute_project_spl_tax<'info>(
+        ctx: Context<'_, '_, '_, 'info, DistributeProjectSPLTax<'info>>,
+        args: DistributeProjectTaxArgs,
+    ) -> Result<()> {
+        route_distribute_project_spl_tax(ctx, args)
+    }
+
+    pub fn distribute_project_sol_tax<'info>(
+        ctx: Context<'_, '_, '_, 'info, DistributeProjectSOLTax<'info>>,
+        args: DistributeProjectTaxArgs,
+    ) -> Result<()> {
+        route_distribute_project_sol_tax(ctx, args)
+    }
+
+    pub fn register_referral(ctx: Context<RegisterReferral>) -> Result<()> {
+        referral_register(ctx)
+    }
+
+    pub fn claim_referral_spl_fees(
+        ctx: Context<ClaimReferralSPLFees>,
+        args: ClaimReferralFeesArgs,
+    ) -> Result<()> {
+        referral_claim_spl_fees(ctx, args)
+    }
+
+    pub fn claim_referral_sol_fees(
+        ctx: Context<ClaimReferralSOLFees>,
+        args: ClaimReferralFeesArgs,
+    ) -> Result<()> {
+        referral_claim_sol_fees(ctx, args)
+    }
 }
@@ -19,6 +19,7 @@ pub const META_LIST_ACCOUNT_SEED: &[u8] = b"extra-account-metas";
 pub const PROJECT_METADATA_SEED: &[u8] = b"project-metadata";
 pub const MINT_ACCOUNT_SEED: &[u8] = b"token-mint-account";
 pub const MAX_TAX_RATE_DENOMINATOR: u64 = 10000;
+pub const SOL_DECIMALS: u8 = 9;
 
 #[error_code]
 pub enum Errors {
@@ -26,6 +27,48 @@ pub enum Errors {
     SignerIsNotAdmin,
     #[msg("TaxRateNotValid")]
     TaxRateNotValid,
+    #[msg("CurveOverflow")]
+    CurveOverflow,
+    #[msg("InsufficientSupply")]
+    InsufficientSupply,
+    #[msg("ZeroOutput")]
+    ZeroOutput,
+    #[msg("InvalidCurveParameters")]
+    InvalidCurveParameters,
+    #[msg("SlippageExceeded")]
+    SlippageExceeded,
+    #[msg("CounterOverflow")]
+    CounterOverflow,
+    #[msg("DecimalsNotValid")]
+    DecimalsNotValid,
+    #[msg("MaxPayExceeded")]
+    MaxPayExceeded,
+    #[msg("MaxBurnExceeded")]
+    MaxBurnExceeded,
+    #[msg("VaultInsolvent")]
+    VaultInsolvent,
+    #[msg("MaxSupplyReached")]
+    MaxSupplyReached,
+    #[msg("NoPendingCurve")]
+    NoPendingCurve,
+    #[msg("CurveTimelockActive")]
+    CurveTimelockActive,
+    #[msg("CurveNotEnabled")]
+    CurveNotEnabled,
+    #[msg("SelfReferral")]
+    SelfReferral,
+    #[msg("RevenueSplitNotValid")]
+    RevenueSplitNotValid,
+    #[msg("RecipientNotValid")]
+    RecipientNotValid,
+    #[msg("ProjectTaxIsSplit")]
+    ProjectTaxIsSplit,
+    #[msg("ProjectAccountsNotValid")]
+    ProjectAccountsNotValid,
+    #[msg("CurveChangePending")]
+    CurveChangePending,
+    #[msg("AccountAlreadyMigrated")]
+    AccountAlreadyMigrated,
 }
 
 #[inline(never)]
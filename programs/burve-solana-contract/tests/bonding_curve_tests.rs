@@ -0,0 +1,78 @@
+// tests/bonding_curve_tests.rs
+use burve_solana_contract::calculations::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_decimals_to_wad() {
+        let decimals = TokenDecimals::new(6, 9).unwrap();
+
+        assert_eq!(decimals.raising_to_wad(1_000_000), WAD);
+        assert_eq!(decimals.project_to_wad(1_000_000_000), WAD);
+        assert_eq!(
+            decimals.project_to_wad(u64::MAX),
+            u64::MAX as u128 * 1_000_000_000
+        );
+    }
+
+    #[test]
+    fn test_token_decimals_from_wad() {
+        let decimals = TokenDecimals::new(6, 18).unwrap();
+
+        assert_eq!(
+            decimals.raising_from_wad(WAD, Rounding::Down),
+            Ok(1_000_000)
+        );
+        assert_eq!(
+            decimals.raising_from_wad(WAD + 1, Rounding::Down),
+            Ok(1_000_000)
+        );
+        assert_eq!(
+            decimals.raising_from_wad(WAD + 1, Rounding::Up),
+            Ok(1_000_001)
+        );
+        assert_eq!(
+            decimals.project_from_wad(WAD + 1, Rounding::Down),
+            Ok(WAD as u64 + 1)
+        );
+        assert_eq!(
+            decimals.project_from_wad(u64::MAX as u128 + 1, Rounding::Down),
+            Err(CurveError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_token_decimals_bounds() {
+        assert!(TokenDecimals::new(0, 18).is_ok());
+        assert_eq!(
+            TokenDecimals::new(19, 9),
+            Err(CurveError::InvalidParameters)
+        );
+        assert_eq!(
+            TokenDecimals::new(9, 19),
+            Err(CurveError::InvalidParameters)
+        );
+    }
+
+    #[test]
+    fn test_18_decimal_raising_token_with_large_supply() {
+        // 18-decimal raising token, 9-decimal project token with a 1e10 token supply
+        let decimals = TokenDecimals::new(18, 9).unwrap();
+        let parameters = Parameters {
+            a: WAD / 1_000,
+            b: 10_000_000_000 * WAD,
+        };
+        let supply = decimals.project_to_wad(10_000_000_000 * 1_000_000_000);
+        let paid = decimals.raising_to_wad(10 * 1_000_000_000_000_000_000);
+
+        let (minted, _) =
+            ExpMixedBondingSwap::calculate_mint_amount_from_bonding_curve(paid, supply, parameters)
+                .unwrap();
+        let minted = decimals.project_from_wad(minted, Rounding::Down).unwrap();
+
+        // Price is 0.001 * e at this supply, so 10 tokens buy about 3678 project tokens
+        assert!(minted > 3_678 * 1_000_000_000 && minted < 3_679 * 1_000_000_000);
+    }
+}
@@ -0,0 +1,208 @@
+// tests/constant_product_bonding_curve_tests.rs
+use burve_solana_contract::calculations::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 1.073B virtual tokens against 30 virtual raising tokens
+    fn setup() -> Parameters {
+        Parameters {
+            a: 1_073_000_000 * WAD,
+            b: 30 * WAD,
+        }
+    }
+
+    #[test]
+    fn test_price_from_constant_product_bonding_curve() {
+        let parameters = Parameters {
+            a: 1_000 * WAD,
+            b: 10 * WAD,
+        };
+
+        assert_eq!(
+            ConstantProductMixedBondingSwap::price(0, parameters.clone()),
+            Ok(WAD / 100)
+        );
+        // Half the virtual tokens gone: Y = 20, X = 500
+        assert_eq!(
+            ConstantProductMixedBondingSwap::price(500 * WAD, parameters),
+            Ok(WAD / 25)
+        );
+    }
+
+    #[test]
+    fn test_calculate_mint_amount_from_constant_product_bonding_curve() {
+        let parameters = Parameters {
+            a: 1_000 * WAD,
+            b: 10 * WAD,
+        };
+
+        // 1000 * 10 = k = (1000 - Δx) * (10 + 10)
+        let (token_amount, raising_token_amount) =
+            ConstantProductMixedBondingSwap::calculate_mint_amount_from_bonding_curve(
+                10 * WAD,
+                0,
+                parameters.clone(),
+            )
+            .unwrap();
+        assert_eq!(token_amount, 500 * WAD);
+        assert_eq!(raising_token_amount, 10 * WAD);
+
+        let (_, burn_return) =
+            ConstantProductMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
+                500 * WAD,
+                500 * WAD,
+                parameters,
+            )
+            .unwrap();
+        assert_eq!(burn_return, 10 * WAD);
+    }
+
+    #[test]
+    fn test_total_raise_is_bounded() {
+        let parameters = setup();
+
+        // No amount of raising token can buy out the virtual reserve
+        let (token_amount, _) =
+            ConstantProductMixedBondingSwap::calculate_mint_amount_from_bonding_curve(
+                u64::MAX as u128 * WAD,
+                0,
+                parameters.clone(),
+            )
+            .unwrap();
+        assert!(token_amount < parameters.a);
+        assert_eq!(
+            ConstantProductMixedBondingSwap::cost_to_mint_exact(parameters.a, 0, parameters),
+            Err(CurveError::InsufficientSupply)
+        );
+    }
+
+    #[test]
+    fn test_mint_burn_round_trip_across_supply_range() {
+        let parameters = setup();
+        let supplies = [
+            0u128,
+            WAD,
+            1_000_000 * WAD,
+            500_000_000 * WAD,
+            1_000_000_000 * WAD,
+        ];
+        let amounts = [1_000u128, WAD / 1_000, WAD, 1_000 * WAD];
+
+        for &supply in supplies.iter() {
+            for &amount in amounts.iter() {
+                let (minted_amount, _) =
+                    ConstantProductMixedBondingSwap::calculate_mint_amount_from_bonding_curve(
+                        amount,
+                        supply,
+                        parameters.clone(),
+                    )
+                    .unwrap();
+                let (_, amount_return) =
+                    ConstantProductMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
+                        minted_amount,
+                        supply + minted_amount,
+                        parameters.clone(),
+                    )
+                    .unwrap();
+
+                // Never pays out more than was paid in
+                assert!(amount_return <= amount);
+                assert!(amount - amount_return <= amount / 1_000_000_000_000 + 3);
+            }
+        }
+    }
+
+    #[test]
+    fn test_exact_output_round_trip() {
+        let parameters = setup();
+        let supplies = [WAD, 1_000_000 * WAD, 500_000_000 * WAD];
+        let amounts = [WAD / 1_000, WAD, 1_000 * WAD];
+
+        for &supply in supplies.iter() {
+            for &amount in amounts.iter() {
+                // Paying the quoted cost mints at least the requested amount
+                let (_, cost) = ConstantProductMixedBondingSwap::cost_to_mint_exact(
+                    amount,
+                    supply,
+                    parameters.clone(),
+                )
+                .unwrap();
+                let (minted_amount, _) =
+                    ConstantProductMixedBondingSwap::calculate_mint_amount_from_bonding_curve(
+                        cost,
+                        supply,
+                        parameters.clone(),
+                    )
+                    .unwrap();
+                assert!(minted_amount >= amount);
+
+                // Burning the quoted amount returns the requested proceeds, give or
+                // take the rounding of Y0 * Δx / (X + Δx) scaled up by X0 / X
+                let token_amount =
+                    match ConstantProductMixedBondingSwap::tokens_to_burn_for_exact_out(
+                        amount,
+                        supply,
+                        parameters.clone(),
+                    ) {
+                        Ok((token_amount, _)) => token_amount,
+                        // The reserve does not hold that much
+                        Err(CurveError::InsufficientSupply) => continue,
+                        Err(err) => panic!("unexpected error {:?}", err),
+                    };
+                let (_, burn_return) =
+                    ConstantProductMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
+                        token_amount,
+                        supply,
+                        parameters.clone(),
+                    )
+                    .unwrap();
+                assert!(token_amount <= supply);
+                assert!(burn_return + parameters.a / (parameters.a - supply) + 1 >= amount);
+            }
+        }
+    }
+
+    #[test]
+    fn test_constant_product_bonding_curve_errors() {
+        let parameters = setup();
+
+        assert_eq!(
+            ConstantProductMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
+                WAD + 1,
+                WAD,
+                parameters.clone(),
+            ),
+            Err(CurveError::InsufficientSupply)
+        );
+        assert_eq!(
+            ConstantProductMixedBondingSwap::calculate_mint_amount_from_bonding_curve(
+                WAD,
+                parameters.a,
+                parameters.clone(),
+            ),
+            Err(CurveError::InsufficientSupply)
+        );
+        assert_eq!(
+            ConstantProductMixedBondingSwap::calculate_mint_amount_from_bonding_curve(
+                0,
+                0,
+                parameters.clone(),
+            ),
+            Err(CurveError::ZeroOutput)
+        );
+        assert_eq!(
+            ConstantProductMixedBondingSwap::tokens_to_burn_for_exact_out(WAD, WAD, parameters),
+            Err(CurveError::InsufficientSupply)
+        );
+        assert_eq!(
+            ConstantProductMixedBondingSwap::price(0, Parameters { a: 0, b: WAD }),
+            Err(CurveError::InvalidParameters)
+        );
+        assert_eq!(
+            ConstantProductMixedBondingSwap::price(0, Parameters { a: WAD, b: 0 }),
+            Err(CurveError::InvalidParameters)
+        );
+    }
+}
@@ -0,0 +1,129 @@
+// tests/curve_change_tests.rs
+use burve_solana_contract::calculations::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use burve_solana_contract::{
+        due_pending_curve, settle_vault_to_curve, BondingCurveType, CurveSettlement, Errors,
+        PendingCurve, CURVE_CHANGE_TIMELOCK,
+    };
+
+    const PROPOSED_AT: i64 = 1_700_000_000;
+    const SUPPLY: u64 = 2_000_000_000_000_000;
+
+    // Linear curve with base price `b` and the fixed slope `a` of 1e9, for a
+    // 9 decimal token raising SOL
+    fn linear(b: u128) -> BondingCurveType {
+        BondingCurveType::Linear {
+            a: 1_000_000_000,
+            b,
+        }
+    }
+
+    fn decimals() -> TokenDecimals {
+        TokenDecimals::new(9, 9).unwrap()
+    }
+
+    fn pending_curve() -> PendingCurve {
+        PendingCurve {
+            bonding_curve_type: linear(WAD / 500),
+            effective_time: PROPOSED_AT + CURVE_CHANGE_TIMELOCK,
+        }
+    }
+
+    // Reserve `bonding_curve_type` needs at SUPPLY, in raising token units
+    fn reserve(bonding_curve_type: &BondingCurveType) -> u64 {
+        let reserve =
+            curve_reserve_at_supply(bonding_curve_type, decimals().project_to_wad(SUPPLY)).unwrap();
+        decimals().raising_from_wad(reserve, Rounding::Up).unwrap()
+    }
+
+    #[test]
+    fn test_curve_needs_a_proposal() {
+        assert_eq!(
+            due_pending_curve(None, PROPOSED_AT),
+            Err(Errors::NoPendingCurve.into())
+        );
+    }
+
+    #[test]
+    fn test_curve_waits_for_the_timelock() {
+        let pending_curve = pending_curve();
+        assert_eq!(
+            due_pending_curve(Some(&pending_curve), PROPOSED_AT),
+            Err(Errors::CurveTimelockActive.into())
+        );
+        assert_eq!(
+            due_pending_curve(Some(&pending_curve), pending_curve.effective_time - 1),
+            Err(Errors::CurveTimelockActive.into())
+        );
+        assert_eq!(
+            due_pending_curve(Some(&pending_curve), pending_curve.effective_time),
+            Ok(linear(WAD / 500))
+        );
+        assert_eq!(
+            due_pending_curve(Some(&pending_curve), pending_curve.effective_time + 1),
+            Ok(linear(WAD / 500))
+        );
+    }
+
+    #[test]
+    fn test_admin_covers_a_steeper_curve() {
+        let vault = reserve(&linear(WAD / 1_000));
+        let new_reserve = reserve(&linear(WAD / 500));
+        assert!(new_reserve > vault);
+
+        assert_eq!(
+            settle_vault_to_curve(linear(WAD / 500), vault, 0, SUPPLY, decimals()),
+            Ok(CurveSettlement {
+                reserve: new_reserve,
+                shortfall: new_reserve - vault,
+                excess: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn test_project_fees_take_the_excess_of_a_flatter_curve() {
+        let vault = reserve(&linear(WAD / 500));
+        let new_reserve = reserve(&linear(WAD / 1_000));
+        assert!(new_reserve < vault);
+
+        assert_eq!(
+            settle_vault_to_curve(linear(WAD / 1_000), vault, 0, SUPPLY, decimals()),
+            Ok(CurveSettlement {
+                reserve: new_reserve,
+                shortfall: 0,
+                excess: vault - new_reserve,
+            })
+        );
+    }
+
+    #[test]
+    fn test_settlement_leaves_the_fees_in_the_vault() {
+        let fees = 5_000;
+        let vault = reserve(&linear(WAD / 1_000)) + fees;
+
+        // Same curve, nothing moves
+        assert_eq!(
+            settle_vault_to_curve(linear(WAD / 1_000), vault, fees, SUPPLY, decimals()),
+            Ok(CurveSettlement {
+                reserve: vault - fees,
+                shortfall: 0,
+                excess: 0,
+            })
+        );
+
+        // The fees are never paid out as excess
+        let new_reserve = reserve(&linear(WAD / 2_000));
+        assert_eq!(
+            settle_vault_to_curve(linear(WAD / 2_000), vault, fees, SUPPLY, decimals()),
+            Ok(CurveSettlement {
+                reserve: new_reserve,
+                shortfall: 0,
+                excess: vault - fees - new_reserve,
+            })
+        );
+    }
+}
@@ -0,0 +1,283 @@
+// tests/curve_designer_tests.rs
+use burve_solana_contract::calculations::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use burve_solana_contract::BondingCurveType;
+
+    // From 0.001 to 0.01 over 10M tokens, holding 40_000 at that point
+    fn setup() -> LaunchTargets {
+        LaunchTargets {
+            initial_price: WAD / 1_000,
+            target_price: WAD / 100,
+            target_supply: 10_000_000 * WAD,
+            target_raise: Some(40_000 * WAD),
+            max_supply: 10_000_000 * WAD,
+        }
+    }
+
+    // The same targets, leaving the raise to the curve
+    fn setup_without_raise() -> LaunchTargets {
+        LaunchTargets {
+            target_raise: None,
+            ..setup()
+        }
+    }
+
+    fn assert_close(value: u128, expected: u128) {
+        assert!(
+            value.abs_diff(expected) <= expected / 1_000_000_000 + 1_000,
+            "{} is not close to {}",
+            value,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_design_hits_initial_and_target_price() {
+        let targets = setup_without_raise();
+
+        for kind in [
+            CurveKind::Linear,
+            CurveKind::Exponential,
+            CurveKind::ConstantProduct,
+            CurveKind::Sigmoid,
+        ] {
+            let bonding_curve_type = design_curve(kind, &targets).unwrap();
+            let start = report_curve(&bonding_curve_type, 0).unwrap();
+            let target = report_curve(&bonding_curve_type, targets.target_supply).unwrap();
+
+            assert_close(start.price, targets.initial_price);
+            assert_close(target.price, targets.target_price);
+            assert_eq!(start.reserve, 0);
+            assert!(target.reserve > 0);
+        }
+    }
+
+    #[test]
+    fn test_design_linear() {
+        let targets = setup_without_raise();
+
+        // k = 0.009 / 10M, and the reserve is the area under the line
+        let bonding_curve_type = design_curve(CurveKind::Linear, &targets).unwrap();
+        assert_eq!(
+            bonding_curve_type,
+            BondingCurveType::Linear {
+                a: 900_000_000,
+                b: WAD / 1_000,
+            }
+        );
+        let report = report_curve(&bonding_curve_type, targets.target_supply).unwrap();
+        assert_eq!(report.reserve, 55_000 * WAD);
+
+        // The two prices leave no room for any other raise
+        let raised = LaunchTargets {
+            target_raise: Some(55_000 * WAD),
+            ..targets.clone()
+        };
+        assert_eq!(
+            design_curve(CurveKind::Linear, &raised),
+            Ok(bonding_curve_type)
+        );
+        assert_eq!(
+            design_curve(CurveKind::Linear, &setup()),
+            Err(CurveError::InvalidParameters)
+        );
+    }
+
+    #[test]
+    fn test_design_checks_target_raise() {
+        let targets = setup();
+
+        // Only the power curve can be fitted to a 40_000 raise at these prices
+        for kind in [
+            CurveKind::Linear,
+            CurveKind::Exponential,
+            CurveKind::ConstantProduct,
+            CurveKind::Stepped,
+            CurveKind::Sigmoid,
+        ] {
+            assert_eq!(
+                design_curve(kind, &targets),
+                Err(CurveError::InvalidParameters),
+                "{:?}",
+                kind
+            );
+
+            // The raise each of them holds is accepted back
+            let bonding_curve_type = design_curve(kind, &setup_without_raise()).unwrap();
+            let reserve =
+                curve_reserve_at_supply(&bonding_curve_type, targets.target_supply).unwrap();
+            let raised = LaunchTargets {
+                target_raise: Some(reserve),
+                ..targets.clone()
+            };
+            assert_eq!(design_curve(kind, &raised), Ok(bonding_curve_type));
+        }
+    }
+
+    #[test]
+    fn test_design_power_hits_target_raise() {
+        let targets = setup();
+
+        let bonding_curve_type = design_curve(CurveKind::Power, &targets).unwrap();
+        assert!(matches!(
+            bonding_curve_type,
+            BondingCurveType::Power { b, .. } if b == 4 * WAD / 10
+        ));
+        // m = 0.01 / 1e7 ^ 1.5 is only ~316_228 wei, so truncating it costs a few ppm
+        let report = report_curve(&bonding_curve_type, targets.target_supply).unwrap();
+        assert!(report.price.abs_diff(targets.target_price) <= targets.target_price / 100_000);
+        let target_raise = targets.target_raise.unwrap();
+        assert!(report.reserve.abs_diff(target_raise) <= target_raise / 100_000);
+    }
+
+    #[test]
+    fn test_design_stepped() {
+        let targets = setup_without_raise();
+
+        let bonding_curve_type = design_curve(CurveKind::Stepped, &targets).unwrap();
+        let tiers = match &bonding_curve_type {
+            BondingCurveType::Stepped { tiers } => tiers.clone(),
+            other => panic!("unexpected curve {:?}", other),
+        };
+        assert_eq!(tiers.len(), burve_solana_contract::MAX_STEPPED_TIERS);
+        assert_eq!(tiers.last().unwrap().supply_breakpoint, targets.max_supply);
+        assert_eq!(tiers.last().unwrap().price, targets.target_price);
+        assert_eq!(
+            report_curve(&bonding_curve_type, 0).unwrap().price,
+            targets.initial_price
+        );
+        assert_eq!(
+            report_curve(&bonding_curve_type, targets.max_supply),
+            Err(CurveError::InsufficientSupply)
+        );
+    }
+
+    #[test]
+    fn test_design_stepped_below_max_supply() {
+        let targets = LaunchTargets {
+            target_supply: 4_000_000 * WAD,
+            ..setup_without_raise()
+        };
+
+        // The target price is reached at the target supply and held up to the
+        // max supply
+        let bonding_curve_type = design_curve(CurveKind::Stepped, &targets).unwrap();
+        let tiers = match &bonding_curve_type {
+            BondingCurveType::Stepped { tiers } => tiers.clone(),
+            other => panic!("unexpected curve {:?}", other),
+        };
+        assert_eq!(tiers.len(), burve_solana_contract::MAX_STEPPED_TIERS);
+        assert_eq!(
+            tiers[tiers.len() - 2].supply_breakpoint,
+            targets.target_supply
+        );
+        assert_eq!(tiers.last().unwrap().supply_breakpoint, targets.max_supply);
+        assert_eq!(
+            report_curve(&bonding_curve_type, 0).unwrap().price,
+            targets.initial_price
+        );
+        assert_eq!(
+            report_curve(&bonding_curve_type, targets.target_supply)
+                .unwrap()
+                .price,
+            targets.target_price
+        );
+        let below_target = report_curve(&bonding_curve_type, targets.target_supply - 1).unwrap();
+        assert!(below_target.price < targets.target_price);
+        assert_eq!(
+            report_curve(&bonding_curve_type, targets.max_supply - 1)
+                .unwrap()
+                .price,
+            targets.target_price
+        );
+    }
+
+    #[test]
+    fn test_report_matches_burn_of_whole_supply() {
+        let targets = setup_without_raise();
+        let bonding_curve_type = design_curve(CurveKind::Exponential, &targets).unwrap();
+        let parameters = match bonding_curve_type.clone() {
+            BondingCurveType::Exponential { a, b } => Parameters { a, b },
+            other => panic!("unexpected curve {:?}", other),
+        };
+
+        let supply = 1_234_567 * WAD;
+        let report = report_curve(&bonding_curve_type, supply).unwrap();
+        assert_eq!(
+            ExpMixedBondingSwap::price(supply, parameters.clone()),
+            Ok(report.price)
+        );
+        assert_eq!(
+            ExpMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
+                supply, supply, parameters,
+            ),
+            Ok((supply, report.reserve))
+        );
+    }
+
+    #[test]
+    fn test_design_errors() {
+        let targets = setup();
+
+        // The price must rise towards the target
+        let flat = LaunchTargets {
+            target_price: targets.initial_price,
+            ..targets.clone()
+        };
+        assert_eq!(
+            design_curve(CurveKind::Linear, &flat),
+            Err(CurveError::InvalidParameters)
+        );
+        let short = LaunchTargets {
+            max_supply: targets.target_supply - 1,
+            ..targets.clone()
+        };
+        assert_eq!(
+            design_curve(CurveKind::Exponential, &short),
+            Err(CurveError::InvalidParameters)
+        );
+        // Raises more than the market cap at the target supply
+        let over_raised = LaunchTargets {
+            target_raise: Some(200_000 * WAD),
+            ..targets.clone()
+        };
+        assert_eq!(
+            design_curve(CurveKind::Power, &over_raised),
+            Err(CurveError::InvalidParameters)
+        );
+        // The power curve has nothing to fit without a raise
+        assert_eq!(
+            design_curve(CurveKind::Power, &setup_without_raise()),
+            Err(CurveError::InvalidParameters)
+        );
+        let nothing_raised = LaunchTargets {
+            target_raise: Some(0),
+            ..targets.clone()
+        };
+        assert_eq!(
+            design_curve(CurveKind::Linear, &nothing_raised),
+            Err(CurveError::InvalidParameters)
+        );
+        // The virtual token reserve runs out before the max supply
+        let long = LaunchTargets {
+            max_supply: 20_000_000 * WAD,
+            ..targets.clone()
+        };
+        assert_eq!(
+            design_curve(CurveKind::ConstantProduct, &long),
+            Err(CurveError::InvalidParameters)
+        );
+        // The exponential cannot be evaluated that far out
+        let far = LaunchTargets {
+            max_supply: 10_000_000_000 * WAD,
+            ..targets
+        };
+        assert_eq!(
+            design_curve(CurveKind::Exponential, &far),
+            Err(CurveError::Overflow)
+        );
+    }
+}
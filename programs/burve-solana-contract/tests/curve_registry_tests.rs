@@ -0,0 +1,137 @@
+// tests/curve_registry_tests.rs
+use burve_solana_contract::calculations::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use burve_solana_contract::{stepped_tiers, BondingCurveType, StepTier};
+
+    const SUPPLY: u128 = 2_000_000 * WAD;
+
+    fn tiers() -> Vec<StepTier> {
+        vec![
+            StepTier {
+                supply_breakpoint: 1_000_000 * WAD,
+                price: WAD / 1_000,
+            },
+            StepTier {
+                supply_breakpoint: 1_000_000_000 * WAD,
+                price: WAD / 500,
+            },
+        ]
+    }
+
+    // Dispatches the same way as calling the curve directly
+    fn assert_dispatches_to<C: BondingCurve>(
+        bonding_curve_type: BondingCurveType,
+        params: C::Params,
+    ) where
+        C::Params: Clone,
+    {
+        assert_eq!(
+            curve_mint_amount(&bonding_curve_type, 100 * WAD, SUPPLY),
+            C::calculate_mint_amount_from_bonding_curve(100 * WAD, SUPPLY, params.clone())
+        );
+        assert_eq!(
+            curve_burn_amount(&bonding_curve_type, 1_000 * WAD, SUPPLY),
+            C::calculate_burn_amount_from_bonding_curve(1_000 * WAD, SUPPLY, params.clone())
+        );
+        assert_eq!(
+            curve_cost_to_mint_exact(&bonding_curve_type, 1_000 * WAD, SUPPLY),
+            C::cost_to_mint_exact(1_000 * WAD, SUPPLY, params.clone())
+        );
+        assert_eq!(
+            curve_tokens_to_burn_for_exact_out(&bonding_curve_type, WAD, SUPPLY),
+            C::tokens_to_burn_for_exact_out(WAD, SUPPLY, params.clone())
+        );
+        assert_eq!(
+            curve_reserve_at_supply(&bonding_curve_type, SUPPLY),
+            C::reserve_at_supply(SUPPLY, params)
+        );
+    }
+
+    #[test]
+    fn test_dispatches_each_curve() {
+        assert_dispatches_to::<LinearMixedBondingSwap>(
+            BondingCurveType::Linear {
+                a: 1_000_000_000,
+                b: WAD / 1_000,
+            },
+            Parameters {
+                a: 1_000_000_000,
+                b: WAD / 1_000,
+            },
+        );
+        assert_dispatches_to::<ExpMixedBondingSwap>(
+            BondingCurveType::Exponential {
+                a: WAD / 1_000,
+                b: 100_000_000 * WAD,
+            },
+            Parameters {
+                a: WAD / 1_000,
+                b: 100_000_000 * WAD,
+            },
+        );
+        assert_dispatches_to::<PowerMixedBondingSwap>(
+            BondingCurveType::Power {
+                a: WAD / 1_000,
+                b: WAD / 2,
+            },
+            Parameters {
+                a: WAD / 1_000,
+                b: WAD / 2,
+            },
+        );
+        assert_dispatches_to::<ConstantProductMixedBondingSwap>(
+            BondingCurveType::ConstantProduct {
+                virtual_token_reserve: 1_073_000_000 * WAD,
+                virtual_raising_reserve: 30 * WAD,
+            },
+            Parameters {
+                a: 1_073_000_000 * WAD,
+                b: 30 * WAD,
+            },
+        );
+        assert_dispatches_to::<SteppedMixedBondingSwap>(
+            BondingCurveType::Stepped { tiers: tiers() },
+            stepped_tiers(tiers()),
+        );
+        assert_dispatches_to::<SigmoidMixedBondingSwap>(
+            BondingCurveType::Sigmoid {
+                max_price: WAD / 100,
+                midpoint_supply: 5_000_000 * WAD,
+                steepness: WAD / 1_000_000,
+            },
+            SigmoidParameters {
+                max_price: WAD / 100,
+                midpoint_supply: 5_000_000 * WAD,
+                steepness: WAD / 1_000_000,
+            },
+        );
+    }
+
+    #[test]
+    fn test_curve_kind_flags() {
+        assert_eq!(
+            curve_kind(&BondingCurveType::Stepped { tiers: tiers() }),
+            CurveKind::Stepped
+        );
+        assert_eq!(
+            curve_kind(&BondingCurveType::Power { a: 1, b: 1 }),
+            CurveKind::Power
+        );
+
+        // One distinct bit per kind
+        let mask = curve_kinds_mask(&ALL_CURVE_KINDS);
+        assert_eq!(mask.count_ones() as usize, ALL_CURVE_KINDS.len());
+        for kind in ALL_CURVE_KINDS {
+            assert_eq!(kind.flag().count_ones(), 1);
+            assert_ne!(mask & kind.flag(), 0);
+        }
+        assert_eq!(
+            curve_kinds_mask(&[CurveKind::Linear, CurveKind::Sigmoid]),
+            CurveKind::Linear.flag() | CurveKind::Sigmoid.flag()
+        );
+        assert_eq!(curve_kinds_mask(&[]), 0);
+    }
+}
@@ -0,0 +1,200 @@
+// tests/curve_validation_tests.rs
+use burve_solana_contract::calculations::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use burve_solana_contract::{
+        BondingCurveType, StepTier, DEFAULT_MAX_SUPPLY, MAX_STEPPED_TIERS,
+    };
+
+    fn assert_rejected(bonding_curve_type: BondingCurveType) {
+        assert_eq!(
+            validate_curve(&bonding_curve_type, DEFAULT_MAX_SUPPLY),
+            Err(CurveError::InvalidParameters),
+            "{:?} was accepted",
+            bonding_curve_type
+        );
+    }
+
+    fn tier(supply_breakpoint: u128, price: u128) -> StepTier {
+        StepTier {
+            supply_breakpoint,
+            price,
+        }
+    }
+
+    #[test]
+    fn test_accepts_reasonable_curves() {
+        for bonding_curve_type in [
+            BondingCurveType::Linear {
+                a: 1_000_000_000,
+                b: WAD / 1_000,
+            },
+            BondingCurveType::Exponential {
+                a: WAD / 1_000,
+                b: 100_000_000 * WAD,
+            },
+            BondingCurveType::Power {
+                a: WAD / 1_000,
+                b: WAD / 2,
+            },
+            BondingCurveType::ConstantProduct {
+                virtual_token_reserve: 1_073_000_000 * WAD,
+                virtual_raising_reserve: 30 * WAD,
+            },
+            BondingCurveType::Stepped {
+                tiers: vec![
+                    tier(1_000_000 * WAD, WAD / 1_000),
+                    tier(5_000_000 * WAD, WAD / 500),
+                ],
+            },
+            BondingCurveType::Sigmoid {
+                max_price: WAD / 100,
+                midpoint_supply: 5_000_000 * WAD,
+                steepness: WAD / 1_000_000,
+            },
+        ] {
+            assert_eq!(
+                validate_curve(&bonding_curve_type, DEFAULT_MAX_SUPPLY),
+                Ok(()),
+                "{:?} was rejected",
+                bonding_curve_type
+            );
+        }
+    }
+
+    #[test]
+    fn test_rejects_zero_parameters() {
+        assert_rejected(BondingCurveType::Linear { a: 0, b: 0 });
+        assert_rejected(BondingCurveType::Exponential { a: WAD, b: 0 });
+        assert_rejected(BondingCurveType::Exponential { a: 0, b: WAD });
+        assert_rejected(BondingCurveType::Power { a: 0, b: WAD / 2 });
+        assert_rejected(BondingCurveType::ConstantProduct {
+            virtual_token_reserve: 0,
+            virtual_raising_reserve: WAD,
+        });
+        assert_rejected(BondingCurveType::ConstantProduct {
+            virtual_token_reserve: WAD,
+            virtual_raising_reserve: 0,
+        });
+        assert_rejected(BondingCurveType::Sigmoid {
+            max_price: 0,
+            midpoint_supply: WAD,
+            steepness: WAD,
+        });
+        assert_rejected(BondingCurveType::Sigmoid {
+            max_price: WAD,
+            midpoint_supply: WAD,
+            steepness: 0,
+        });
+    }
+
+    #[test]
+    fn test_rejects_price_overflow_at_max_supply() {
+        // e ^ (1e9 / 1e6) is far past what `exp_wad` can take
+        assert_rejected(BondingCurveType::Exponential {
+            a: WAD / 1_000,
+            b: 1_000_000 * WAD,
+        });
+        // The same curve is fine for a project that stops at 10M tokens
+        assert_eq!(
+            validate_curve(
+                &BondingCurveType::Exponential {
+                    a: WAD / 1_000,
+                    b: 1_000_000 * WAD,
+                },
+                10_000_000 * WAD
+            ),
+            Ok(())
+        );
+        // k * 1e9 whole tokens no longer fits
+        assert_rejected(BondingCurveType::Linear {
+            a: u128::MAX / 1_000,
+            b: WAD,
+        });
+    }
+
+    #[test]
+    fn test_rejects_zero_price() {
+        // The price rounds to zero over the first whole token
+        assert_rejected(BondingCurveType::ConstantProduct {
+            virtual_token_reserve: 1_000_000_000 * WAD,
+            virtual_raising_reserve: 1,
+        });
+        assert_rejected(BondingCurveType::Sigmoid {
+            max_price: WAD / 100,
+            midpoint_supply: 100_000_000 * WAD,
+            steepness: WAD,
+        });
+        // Out of the reserve ratio range
+        assert_rejected(BondingCurveType::Power { a: WAD, b: 0 });
+        assert_rejected(BondingCurveType::Power { a: WAD, b: 2 * WAD });
+    }
+
+    #[test]
+    fn test_constant_product_only_checked_up_to_its_virtual_reserve() {
+        // Minting stops short of X0 on its own, so a small X0 is fine
+        assert_eq!(
+            validate_curve(
+                &BondingCurveType::ConstantProduct {
+                    virtual_token_reserve: 1_000_000 * WAD,
+                    virtual_raising_reserve: 1_000 * WAD,
+                },
+                DEFAULT_MAX_SUPPLY
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_rejects_invalid_tiers() {
+        assert_rejected(BondingCurveType::Stepped { tiers: vec![] });
+        assert_rejected(BondingCurveType::Stepped {
+            tiers: vec![tier(2 * WAD, WAD), tier(WAD, WAD)],
+        });
+        assert_rejected(BondingCurveType::Stepped {
+            tiers: vec![tier(WAD, WAD), tier(2 * WAD, 0)],
+        });
+        assert_rejected(BondingCurveType::Stepped {
+            tiers: (1..=MAX_STEPPED_TIERS as u128 + 1)
+                .map(|i| tier(i * WAD, i * WAD))
+                .collect(),
+        });
+        // The reserve of the full tier list does not fit
+        assert_rejected(BondingCurveType::Stepped {
+            tiers: vec![tier(u128::MAX / 2, u128::MAX / 2)],
+        });
+    }
+
+    #[test]
+    fn test_designed_curves_validate() {
+        let targets = LaunchTargets {
+            initial_price: WAD / 1_000,
+            target_price: WAD / 100,
+            target_supply: 10_000_000 * WAD,
+            target_raise: None,
+            max_supply: 10_000_000 * WAD,
+        };
+
+        for kind in [
+            CurveKind::Linear,
+            CurveKind::Exponential,
+            CurveKind::Power,
+            CurveKind::ConstantProduct,
+            CurveKind::Stepped,
+            CurveKind::Sigmoid,
+        ] {
+            // Only the power curve is fitted to a raise
+            let targets = LaunchTargets {
+                target_raise: (kind == CurveKind::Power).then_some(40_000 * WAD),
+                ..targets.clone()
+            };
+            let bonding_curve_type = design_curve(kind, &targets).unwrap();
+            assert_eq!(
+                validate_curve(&bonding_curve_type, targets.max_supply),
+                Ok(())
+            );
+        }
+    }
+}
@@ -7,18 +7,16 @@ mod tests {
     use burve_solana_contract::Parameters;
     use rand::Rng;
 
-    // FIXME This does NOT work right now, because currently we can't handle a decimal of 18 digits.
-    // In solana, a standard of decimal is 9 digits, so the balance storage is using u64.
-    // The max value of u64 is 18,446,744,073,709,551,615, which is around 10^19.
-    // So, a token with decimal of 9 digits and max supply of 10^10 is the maximum we can handle.
-    fn setup() -> (u64, u64, u64, Parameters) {
-        let supply = 10_000_000 * 1_000_000_000; // 1e7 * 1e18
-        let px = 1_000 * 1_000; // 0.001 * 1e18
-        let tvl = 2_000 * 1_000_000_000; // 2000 * 1e18
+    // Curve amounts are normalised to 18 decimals, independent of the decimals of
+    // the on-chain tokens, see `TokenDecimals`.
+    fn setup() -> (u128, u128, u128, Parameters) {
+        let supply = 10_000_000 * WAD; // 1e7 * 1e18
+        let px = WAD / 1_000; // 0.001 * 1e18
+        let tvl = 2_000 * WAD; // 2000 * 1e18
         let _round = 100;
 
-        let a = 10_000_000; // 0.01 * 1e18
-        let b = (1_000 * 1_000_000_000 / a as u128) as u64; // (1000 * 1e18) / a
+        let a = WAD / 100; // 0.01 * 1e18
+        let b = 1_000 * WAD / a * WAD; // (1000 * 1e18) / a
         let parameters = Parameters { a, b };
 
         (supply, px, tvl, parameters)
@@ -27,21 +25,23 @@ mod tests {
     #[test]
     fn test_calculation() {
         let (_supply, _px, tvl, parameters) = setup();
-        let native_asset = tvl.clone();
+        let native_asset = tvl;
 
         let (token_amount1, raising_token_amount1) =
             ExpMixedBondingSwap::calculate_mint_amount_from_bonding_curve(
-                native_asset.clone(),
+                native_asset,
                 0,
                 parameters.clone(),
-            );
+            )
+            .unwrap();
         let (_token_amount2, _raising_token_amount22) =
             ExpMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
-                token_amount1.clone(),
-                token_amount1.clone(),
+                token_amount1,
+                token_amount1,
                 parameters.clone(),
-            );
-        let price = ExpMixedBondingSwap::price(token_amount1.clone(), parameters.clone());
+            )
+            .unwrap();
+        let price = ExpMixedBondingSwap::price(token_amount1, parameters.clone()).unwrap();
 
         assert_eq!(raising_token_amount1, native_asset);
         assert!(token_amount1 > 0);
@@ -51,29 +51,31 @@ mod tests {
     #[test]
     fn test_estimate_mint_burn() {
         let (_supply, _px, _tvl, parameters) = setup();
-        let user1 = 1_000_000_000u64; // 1 * 1e18
+        let user1 = WAD; // 1 * 1e18
 
         let (received_amount, _raising_token_amount) =
             ExpMixedBondingSwap::calculate_mint_amount_from_bonding_curve(
                 user1,
                 0,
                 parameters.clone(),
-            );
+            )
+            .unwrap();
         assert!(received_amount > 0);
 
-        let erc20_balance = received_amount.clone();
+        let erc20_balance = received_amount;
         let (_, amount_return) = ExpMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
-            erc20_balance.clone(),
-            erc20_balance.clone(),
+            erc20_balance,
+            erc20_balance,
             parameters.clone(),
-        );
+        )
+        .unwrap();
         assert!(amount_return > 0);
     }
 
     #[test]
     fn test_multi_mint() {
         let (_supply, _px, _tvl, parameters) = setup();
-        let user1 = 1_000_000_000u64; // 1 * 1e18
+        let user1 = WAD; // 1 * 1e18
         let round = 100;
 
         for _ in 0..round {
@@ -82,7 +84,8 @@ mod tests {
                     user1,
                     0,
                     parameters.clone(),
-                );
+                )
+                .unwrap();
             assert!(minted_amount > 0);
         }
     }
@@ -90,7 +93,7 @@ mod tests {
     #[test]
     fn test_multi_burn() {
         let (_supply, _px, _tvl, parameters) = setup();
-        let user1 = 5_000_000_000u64; // 5 * 1e18
+        let user1 = 5 * WAD; // 5 * 1e18
         let round = 100;
 
         let (initial_minted_amount, _raising_token_amount) =
@@ -98,23 +101,26 @@ mod tests {
                 user1,
                 0,
                 parameters.clone(),
-            );
+            )
+            .unwrap();
         assert!(initial_minted_amount > 0);
 
         for _ in 0..round {
             let (minted_amount, _raising_token_amount) =
                 ExpMixedBondingSwap::calculate_mint_amount_from_bonding_curve(
-                    1_000_000_000u64,
+                    WAD,
                     0,
                     parameters.clone(),
-                ); // 1000 * 1e18
+                )
+                .unwrap(); // 1 * 1e18
             assert!(minted_amount > 0);
 
             let (_, amount_return) = ExpMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
                 initial_minted_amount,
                 initial_minted_amount,
                 parameters.clone(),
-            );
+            )
+            .unwrap();
             assert!(amount_return > 0);
         }
     }
@@ -126,16 +132,17 @@ mod tests {
 
         for _ in 0..round {
             let mut rng = rand::thread_rng();
-            let amount1 = rng.gen_range(1..=49) * 100_000_000_000_000_000u64; // (1..49) * 1e17
-            let amount2 = rng.gen_range(1..=999) * 100_000_000_000_000_000u64; // (1..999) * 1e17
-            let amount3 = rng.gen_range(100..=999) * 100_000_000_000_000_000u64; // (100..999) * 1e17
+            let amount1 = rng.gen_range(1..=49) * 100_000_000_000_000_000u128; // (1..49) * 1e17
+            let amount2 = rng.gen_range(1..=999) * 100_000_000_000_000_000u128; // (1..999) * 1e17
+            let amount3 = rng.gen_range(100..=999) * 100_000_000_000_000_000u128; // (100..999) * 1e17
 
             let (minted_amount1, _raising_token_amount1) =
                 ExpMixedBondingSwap::calculate_mint_amount_from_bonding_curve(
                     amount1,
                     0,
                     parameters.clone(),
-                );
+                )
+                .unwrap();
             assert!(minted_amount1 > 0);
 
             let (minted_amount2, _raising_token_amount2) =
@@ -143,7 +150,8 @@ mod tests {
                     amount2,
                     0,
                     parameters.clone(),
-                );
+                )
+                .unwrap();
             assert!(minted_amount2 > 0);
 
             let (_, burn_amount_return) =
@@ -151,7 +159,8 @@ mod tests {
                     minted_amount2,
                     minted_amount2,
                     parameters.clone(),
-                );
+                )
+                .unwrap();
             assert!(burn_amount_return > 0);
 
             let (minted_amount3, _raising_token_amount3) =
@@ -159,7 +168,8 @@ mod tests {
                     amount3,
                     0,
                     parameters.clone(),
-                );
+                )
+                .unwrap();
             assert!(minted_amount3 > 0);
 
             if rng.gen_bool(0.2) {
@@ -168,7 +178,8 @@ mod tests {
                         minted_amount3,
                         minted_amount3,
                         parameters.clone(),
-                    );
+                    )
+                    .unwrap();
                 assert!(burn_amount_return > 0);
             }
         }
@@ -177,7 +188,7 @@ mod tests {
     #[test]
     fn test_fuzz() {
         let (_supply, _px, _tvl, parameters) = setup();
-        let amount = 979_999_999_999_999_107u64; // large number * 1e18
+        let amount = 979_999_999_999_999_107u128; // large number * 1e18
         assert!(amount > 0);
 
         let (minted_amount, _raising_token_amount) =
@@ -185,14 +196,211 @@ mod tests {
                 amount,
                 0,
                 parameters.clone(),
-            );
+            )
+            .unwrap();
         assert!(minted_amount > 0);
 
-        let (_, burn_amount_return) = ExpMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
+        let (_, burn_amount_return) =
+            ExpMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
+                minted_amount,
+                minted_amount,
+                parameters.clone(),
+            )
+            .unwrap();
+        assert!(burn_amount_return > 0);
+    }
+
+    #[test]
+    fn test_mint_burn_round_trip() {
+        let (_supply, _px, _tvl, parameters) = setup();
+        let paid = WAD;
+
+        let (minted_amount, _) = ExpMixedBondingSwap::calculate_mint_amount_from_bonding_curve(
+            paid,
+            0,
+            parameters.clone(),
+        )
+        .unwrap();
+        let (_, amount_return) = ExpMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
             minted_amount,
             minted_amount,
             parameters.clone(),
+        )
+        .unwrap();
+        assert!(amount_return > 0);
+        assert!(amount_return <= paid);
+    }
+
+    #[test]
+    fn test_large_supply_fails_cleanly() {
+        let (_supply, _px, _tvl, parameters) = setup();
+        let token_current_supply = parameters.b * 100;
+
+        assert_eq!(
+            ExpMixedBondingSwap::price(token_current_supply, parameters.clone()),
+            Err(CurveError::Overflow)
+        );
+        assert_eq!(
+            ExpMixedBondingSwap::calculate_mint_amount_from_bonding_curve(
+                1_000_000_000,
+                token_current_supply,
+                parameters,
+            ),
+            Err(CurveError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_burn_more_than_supply() {
+        let (_supply, _px, _tvl, parameters) = setup();
+
+        assert_eq!(
+            ExpMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
+                1_000_001, 1_000_000, parameters,
+            ),
+            Err(CurveError::InsufficientSupply)
+        );
+    }
+
+    #[test]
+    fn test_zero_output() {
+        let (_supply, _px, _tvl, parameters) = setup();
+
+        assert_eq!(
+            ExpMixedBondingSwap::calculate_mint_amount_from_bonding_curve(
+                0,
+                1_000_000,
+                parameters.clone(),
+            ),
+            Err(CurveError::ZeroOutput)
+        );
+        assert_eq!(
+            ExpMixedBondingSwap::calculate_burn_amount_from_bonding_curve(0, 1_000_000, parameters,),
+            Err(CurveError::ZeroOutput)
+        );
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        let parameters = Parameters {
+            a: 10_000_000,
+            b: 0,
+        };
+
+        assert_eq!(
+            ExpMixedBondingSwap::calculate_mint_amount_from_bonding_curve(
+                1_000_000_000,
+                0,
+                parameters.clone(),
+            ),
+            Err(CurveError::InvalidParameters)
+        );
+        assert_eq!(
+            ExpMixedBondingSwap::price(0, Parameters { a: 0, b: 100_000 }),
+            Err(CurveError::InvalidParameters)
+        );
+    }
+
+    // The inverse quotes keep a margin for the error of the exponential, worth
+    // about price * b * 1e-16 of the raising token
+    fn exp_error_margin(token_current_supply: u128, parameters: &Parameters) -> u128 {
+        let price = ExpMixedBondingSwap::price(token_current_supply, parameters.clone()).unwrap();
+        price / 1_000_000_000_000_000 * (parameters.b / WAD) + 1_000_000
+    }
+
+    #[test]
+    fn test_cost_to_mint_exact_round_trip() {
+        let (_supply, _px, _tvl, parameters) = setup();
+        let supplies = [0u128, WAD, 100_000 * WAD, 1_000_000 * WAD];
+        let token_amounts = [WAD / 1_000, WAD, 1_000 * WAD, 1_000_000 * WAD];
+
+        for &supply in supplies.iter() {
+            for &token_amount in token_amounts.iter() {
+                let (_, cost) = ExpMixedBondingSwap::cost_to_mint_exact(
+                    token_amount,
+                    supply,
+                    parameters.clone(),
+                )
+                .unwrap();
+                let (minted_amount, _) =
+                    ExpMixedBondingSwap::calculate_mint_amount_from_bonding_curve(
+                        cost,
+                        supply,
+                        parameters.clone(),
+                    )
+                    .unwrap();
+                let (_, burn_return) =
+                    ExpMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
+                        token_amount,
+                        supply + token_amount,
+                        parameters.clone(),
+                    )
+                    .unwrap();
+
+                // Paying the quote mints at least the requested amount, and the
+                // quote is never below what burning those tokens returns
+                assert!(minted_amount >= token_amount);
+                assert!(cost >= burn_return);
+                assert!(cost - burn_return <= exp_error_margin(supply + token_amount, &parameters));
+            }
+        }
+    }
+
+    #[test]
+    fn test_tokens_to_burn_for_exact_out_round_trip() {
+        let (_supply, _px, _tvl, parameters) = setup();
+        let supplies = [WAD, 100_000 * WAD, 1_000_000 * WAD];
+        let raising_amounts = [1_000_000_000u128, WAD / 1_000, WAD, 1_000 * WAD];
+
+        for &supply in supplies.iter() {
+            for &raising_amount in raising_amounts.iter() {
+                let token_amount = match ExpMixedBondingSwap::tokens_to_burn_for_exact_out(
+                    raising_amount,
+                    supply,
+                    parameters.clone(),
+                ) {
+                    Ok((token_amount, _)) => token_amount,
+                    // The reserve does not hold that much
+                    Err(CurveError::InsufficientSupply) => continue,
+                    Err(err) => panic!("unexpected error {:?}", err),
+                };
+                let (_, burn_return) =
+                    ExpMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
+                        token_amount,
+                        supply,
+                        parameters.clone(),
+                    )
+                    .unwrap();
+
+                // Burning the quoted amount returns at least the requested proceeds
+                assert!(token_amount <= supply);
+                assert!(burn_return >= raising_amount);
+                assert!(burn_return - raising_amount <= exp_error_margin(supply, &parameters));
+            }
+        }
+    }
+
+    #[test]
+    fn test_exact_output_errors() {
+        let (_supply, _px, _tvl, parameters) = setup();
+        let (_, reserve) = ExpMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
+            WAD,
+            WAD,
+            parameters.clone(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            ExpMixedBondingSwap::cost_to_mint_exact(0, WAD, parameters.clone()),
+            Err(CurveError::ZeroOutput)
+        );
+        assert_eq!(
+            ExpMixedBondingSwap::tokens_to_burn_for_exact_out(0, WAD, parameters.clone()),
+            Err(CurveError::ZeroOutput)
+        );
+        assert_eq!(
+            ExpMixedBondingSwap::tokens_to_burn_for_exact_out(reserve * 2, WAD, parameters),
+            Err(CurveError::InsufficientSupply)
         );
-        assert!(burn_amount_return > 0);
     }
 }
@@ -0,0 +1,184 @@
+// tests/fee_tests.rs
+use burve_solana_contract::calculations::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::prelude::Pubkey;
+    use burve_solana_contract::{
+        BondingCurveType, BurveBase, ProjectMetadata, MAX_TAX_RATE_DENOMINATOR,
+    };
+
+    // No tax, the default Burve tax, a project's maximum and an odd rate
+    const RATES: [u16; 4] = [0, 100, 1_000, 37];
+
+    fn rates(burve: u16, project: u16) -> TaxRates {
+        TaxRates { burve, project }
+    }
+
+    #[test]
+    fn test_rates_come_from_the_trade_side() {
+        let burve_base = BurveBase {
+            admin: Pubkey::default(),
+            treasury: Pubkey::default(),
+            mint_tax: 100,
+            burn_tax: 50,
+            enabled_curves: 0,
+            referral_share: 2_000,
+        };
+        let project_metadata = ProjectMetadata {
+            admin: Pubkey::default(),
+            treasury: Pubkey::default(),
+            symbol: "BRV".to_string(),
+            mint_tax: 300,
+            burn_tax: 200,
+            raising_token: None,
+            bonding_curve_type: BondingCurveType::Linear { a: 1, b: 1 },
+            burve_tax_counter: 0,
+            max_supply: None,
+            launch_auction: None,
+            pending_curve: None,
+            referral_share: None,
+            referral_fee_counter: 0,
+            revenue_split: vec![],
+            project_tax_counter: 0,
+        };
+        assert_eq!(
+            TaxRates::mint(&burve_base, &project_metadata),
+            rates(100, 300)
+        );
+        assert_eq!(
+            TaxRates::burn(&burve_base, &project_metadata),
+            rates(50, 200)
+        );
+
+        // The project's referral share overrides Burve's
+        assert_eq!(referral_share(&burve_base, &project_metadata), 2_000);
+        let project_metadata = ProjectMetadata {
+            referral_share: Some(5_000),
+            ..project_metadata
+        };
+        assert_eq!(referral_share(&burve_base, &project_metadata), 5_000);
+    }
+
+    #[test]
+    fn test_fees_round_up() {
+        assert_eq!(tax_amount(10_000, 100), Ok(100));
+        assert_eq!(tax_amount(10_001, 100), Ok(101));
+        assert_eq!(tax_amount(1, 1), Ok(1));
+        assert_eq!(tax_amount(0, 100), Ok(0));
+        assert_eq!(tax_amount(12_345, 0), Ok(0));
+        assert_eq!(tax_amount(u64::MAX, 100), Ok(u64::MAX / 100 + 1));
+    }
+
+    #[test]
+    fn test_fees_from_gross_for_each_tax_combination() {
+        for burve in RATES {
+            for project in RATES {
+                for gross_amount in [1_000_000_007u64, 999, 10_000] {
+                    let fees = fees_from_gross(gross_amount, rates(burve, project)).unwrap();
+                    assert_eq!(fees.gross_amount, gross_amount);
+                    assert_eq!(fees.burve_fee, tax_amount(gross_amount, burve).unwrap());
+                    assert_eq!(fees.project_fee, tax_amount(gross_amount, project).unwrap());
+                    assert_eq!(
+                        fees.net_amount + fees.burve_fee + fees.project_fee,
+                        gross_amount
+                    );
+                    // Never less than the exact tax
+                    assert!(
+                        fees.burve_fee as u128 * MAX_TAX_RATE_DENOMINATOR as u128
+                            >= gross_amount as u128 * burve as u128
+                    );
+                    assert!(
+                        fees.project_fee as u128 * MAX_TAX_RATE_DENOMINATOR as u128
+                            >= gross_amount as u128 * project as u128
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_fees_for_net_for_each_tax_combination() {
+        for burve in RATES {
+            for project in RATES {
+                for net_amount in [1u64, 999, 10_000, 1_000_000_007] {
+                    let fees = fees_for_net(net_amount, rates(burve, project)).unwrap();
+                    assert!(fees.net_amount >= net_amount);
+                    assert_eq!(
+                        fees_from_gross(fees.gross_amount, rates(burve, project)),
+                        Ok(fees)
+                    );
+                    // One unit less would not leave the net amount
+                    if let Ok(smaller) =
+                        fees_from_gross(fees.gross_amount - 1, rates(burve, project))
+                    {
+                        assert!(smaller.net_amount < net_amount);
+                    }
+                }
+            }
+        }
+        assert_eq!(
+            fees_for_net(0, rates(100, 100)).map(|fees| fees.gross_amount),
+            Ok(0)
+        );
+    }
+
+    #[test]
+    fn test_fees_too_large_for_the_amount() {
+        // Two fees of one unit each on a single unit
+        assert_eq!(
+            fees_from_gross(1, rates(100, 100)),
+            Err(CurveError::ZeroOutput)
+        );
+        assert_eq!(
+            fees_from_gross(1, rates(100, 0)).map(|fees| fees.net_amount),
+            Ok(0)
+        );
+    }
+
+    #[test]
+    fn test_referral_fee_rounds_down() {
+        assert_eq!(referral_fee(1_000, 2_000), Ok(200));
+        assert_eq!(referral_fee(1_001, 2_000), Ok(200));
+        assert_eq!(referral_fee(4, 2_000), Ok(0));
+        assert_eq!(referral_fee(1_000, 0), Ok(0));
+        assert_eq!(
+            referral_fee(u64::MAX, MAX_TAX_RATE_DENOMINATOR as u16),
+            Ok(u64::MAX)
+        );
+
+        // Never more than the Burve fee it comes out of
+        for burve in RATES {
+            for gross_amount in [1u64, 999, 10_000, 1_000_000_007] {
+                let Ok(fees) = fees_from_gross(gross_amount, rates(burve, 100)) else {
+                    continue;
+                };
+                for share in [0, 1, 2_500, 10_000] {
+                    assert!(referral_fee(fees.burve_fee, share).unwrap() <= fees.burve_fee);
+                }
+            }
+        }
+        assert_eq!(
+            referral_fee(1_000, MAX_TAX_RATE_DENOMINATOR as u16 + 1),
+            Err(CurveError::InvalidParameters)
+        );
+    }
+
+    #[test]
+    fn test_invalid_rates() {
+        let all = MAX_TAX_RATE_DENOMINATOR as u16;
+        assert_eq!(
+            fees_from_gross(1_000, rates(all / 2, all / 2)),
+            Err(CurveError::InvalidParameters)
+        );
+        assert_eq!(
+            fees_for_net(1_000, rates(all, 0)),
+            Err(CurveError::InvalidParameters)
+        );
+        assert_eq!(
+            fees_for_net(u64::MAX, rates(100, 100)),
+            Err(CurveError::Overflow)
+        );
+    }
+}
@@ -0,0 +1,138 @@
+// tests/launch_auction_tests.rs
+use burve_solana_contract::calculations::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Opens at 3x the curve's price and decays away over an hour
+    const START_MULTIPLIER: u128 = 3 * WAD;
+    const DURATION: i64 = 3_600;
+
+    #[test]
+    fn test_premium_decays_linearly() {
+        assert_eq!(
+            launch_premium(START_MULTIPLIER, 0, DURATION),
+            Ok(START_MULTIPLIER)
+        );
+        assert_eq!(
+            launch_premium(START_MULTIPLIER, 1_800, DURATION),
+            Ok(2 * WAD)
+        );
+        assert_eq!(
+            launch_premium(START_MULTIPLIER, 2_700, DURATION),
+            Ok(WAD * 3 / 2)
+        );
+        assert_eq!(
+            launch_premium(START_MULTIPLIER, DURATION, DURATION),
+            Ok(WAD)
+        );
+        assert_eq!(launch_premium(START_MULTIPLIER, 100_000, DURATION), Ok(WAD));
+
+        let mut last_premium = START_MULTIPLIER;
+        for elapsed in (0..=DURATION).step_by(60) {
+            let premium = launch_premium(START_MULTIPLIER, elapsed, DURATION).unwrap();
+            assert!(premium <= last_premium);
+            assert!(premium >= WAD);
+            last_premium = premium;
+        }
+    }
+
+    #[test]
+    fn test_premium_rounds_up() {
+        // 2 / 3 of the way through, 2 * 1 / 3 of a WAD does not divide evenly
+        let premium = launch_premium(START_MULTIPLIER, 1_200, DURATION).unwrap();
+        assert_eq!(premium, WAD + 2 * WAD * 2 / 3 + 1);
+        assert_eq!(launch_premium(WAD, 0, DURATION), Ok(WAD));
+    }
+
+    #[test]
+    fn test_clock_before_launch_charges_full_premium() {
+        assert_eq!(
+            launch_premium(START_MULTIPLIER, -10, DURATION),
+            Ok(START_MULTIPLIER)
+        );
+    }
+
+    #[test]
+    fn test_invalid_auction() {
+        assert_eq!(
+            validate_launch_auction(WAD - 1, DURATION),
+            Err(CurveError::InvalidParameters)
+        );
+        assert_eq!(
+            validate_launch_auction(MAX_LAUNCH_MULTIPLIER + 1, DURATION),
+            Err(CurveError::InvalidParameters)
+        );
+        assert_eq!(
+            validate_launch_auction(START_MULTIPLIER, 0),
+            Err(CurveError::InvalidParameters)
+        );
+        assert_eq!(
+            launch_premium(START_MULTIPLIER, 0, -1),
+            Err(CurveError::InvalidParameters)
+        );
+        assert_eq!(validate_launch_auction(MAX_LAUNCH_MULTIPLIER, 1), Ok(()));
+    }
+
+    #[test]
+    fn test_premium_favours_protocol() {
+        let parameters = Parameters {
+            a: 1_000_000_000,
+            b: WAD / 1_000,
+        };
+        let premium = launch_premium(START_MULTIPLIER, 1_200, DURATION).unwrap();
+        let paid = 1_000 * WAD + 7;
+
+        // Paying with the premium mints what the plain curve gives for a third
+        // of the payment, or slightly less
+        let (minted, _) = LinearMixedBondingSwap::calculate_mint_amount_from_bonding_curve(
+            without_launch_premium(paid, premium).unwrap(),
+            0,
+            parameters.clone(),
+        )
+        .unwrap();
+        let (plain_minted, _) = LinearMixedBondingSwap::calculate_mint_amount_from_bonding_curve(
+            paid,
+            0,
+            parameters.clone(),
+        )
+        .unwrap();
+        assert!(minted < plain_minted);
+
+        // Quoting the premium back never undercharges for those tokens
+        let (_, cost) =
+            LinearMixedBondingSwap::cost_to_mint_exact(minted, 0, parameters.clone()).unwrap();
+        let quote = with_launch_premium(cost, premium).unwrap();
+        assert!(quote >= mul_div(cost, premium, WAD, Rounding::Down).unwrap());
+        assert!(without_launch_premium(quote, premium).unwrap() <= cost);
+        assert_eq!(without_launch_premium(paid, WAD), Ok(paid));
+        assert_eq!(with_launch_premium(paid, WAD), Ok(paid));
+    }
+
+    #[test]
+    fn test_premium_fee_is_rest_of_payment() {
+        // 6 decimal raising token, so a WAD amount rounds to whole units
+        let decimals = TokenDecimals::new(6, 9).unwrap();
+        let paid = 3_000_001;
+        let premium = launch_premium(START_MULTIPLIER, 0, DURATION).unwrap();
+
+        // A third of the payment moves the curve, rounded up in raising units,
+        // and the project keeps the rest
+        let curve_amount = without_launch_premium(decimals.raising_to_wad(paid), premium).unwrap();
+        assert_eq!(
+            launch_premium_fee(paid, curve_amount, decimals),
+            Ok(paid - 1_000_001)
+        );
+
+        // No premium after the auction
+        let curve_amount = without_launch_premium(decimals.raising_to_wad(paid), WAD).unwrap();
+        assert_eq!(launch_premium_fee(paid, curve_amount, decimals), Ok(0));
+
+        // The curve can never take more than was paid
+        assert_eq!(
+            launch_premium_fee(paid, decimals.raising_to_wad(paid + 1), decimals),
+            Err(CurveError::InvalidParameters)
+        );
+    }
+}
@@ -9,15 +9,16 @@ mod tests {
     #[test]
     fn test_calculate_mint_amount_from_linear_bonding_curve() {
         let parameters = Parameters { a: 2, b: 3 };
-        let raising_token_amount: u64 = 100;
-        let token_current_supply: u64 = 50;
+        let raising_token_amount: u128 = 100;
+        let token_current_supply: u128 = 50;
 
         let (token_amount, raising_token_amount) =
             LinearMixedBondingSwap::calculate_mint_amount_from_bonding_curve(
                 raising_token_amount,
                 token_current_supply,
                 parameters,
-            );
+            )
+            .unwrap();
 
         assert!(token_amount > 0);
         assert_eq!(raising_token_amount, 100);
@@ -26,27 +27,280 @@ mod tests {
     #[test]
     fn test_calculate_burn_amount_from_linear_bonding_curve() {
         let parameters = Parameters { a: 2, b: 3 };
-        let token_amount: u64 = 50;
-        let token_current_supply: u64 = 100;
+        let token_amount: u128 = 50 * WAD;
+        let token_current_supply: u128 = 100 * WAD;
 
         let (token_amount, raising_token_amount) =
             LinearMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
                 token_amount,
                 token_current_supply,
                 parameters,
-            );
+            )
+            .unwrap();
 
-        assert!(raising_token_amount > 0);
-        assert_eq!(token_amount, 50);
+        // 50 tokens between price 103 and 203: 2 * (100^2 - 50^2) / 2 + 3 * 50
+        assert_eq!(raising_token_amount, 7_650);
+        assert_eq!(token_amount, 50 * WAD);
     }
 
     #[test]
     fn test_price_from_linear_bonding_curve() {
         let parameters = Parameters { a: 2, b: 3 };
-        let token_current_supply: u64 = 100;
+        let token_current_supply: u128 = 100;
 
-        let price = LinearMixedBondingSwap::price(token_current_supply, parameters);
+        let price = LinearMixedBondingSwap::price(token_current_supply, parameters).unwrap();
 
         assert!(price > 0);
     }
+
+    #[test]
+    fn test_linear_bonding_curve_errors() {
+        let parameters = Parameters { a: 2, b: 3 };
+
+        assert_eq!(
+            LinearMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
+                101,
+                100,
+                parameters.clone(),
+            ),
+            Err(CurveError::InsufficientSupply)
+        );
+        assert_eq!(
+            LinearMixedBondingSwap::calculate_mint_amount_from_bonding_curve(
+                100,
+                0,
+                Parameters { a: 0, b: 0 },
+            ),
+            Err(CurveError::InvalidParameters)
+        );
+        assert_eq!(
+            LinearMixedBondingSwap::calculate_mint_amount_from_bonding_curve(0, 100, parameters),
+            Err(CurveError::ZeroOutput)
+        );
+    }
+
+    #[test]
+    fn test_price_matches_burn_of_one_token() {
+        let parameters = Parameters {
+            a: 2_000,
+            b: 3_000_000,
+        };
+        let token_current_supply: u128 = 1_000 * WAD;
+
+        let price =
+            LinearMixedBondingSwap::price(token_current_supply, parameters.clone()).unwrap();
+        let (_, burn_return) = LinearMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
+            WAD,
+            token_current_supply,
+            parameters,
+        )
+        .unwrap();
+
+        // Burning the last token returns the average of the prices before and after it
+        assert_eq!(price, 5_000_000);
+        assert_eq!(burn_return, 4_999_000);
+    }
+
+    #[test]
+    fn test_mint_burn_round_trip_across_supply_range() {
+        let all_parameters = [
+            Parameters {
+                a: 1_000_000_000,
+                b: 1,
+            },
+            Parameters {
+                a: 2_000,
+                b: 3_000_000,
+            },
+            Parameters {
+                a: 1_000_000_000,
+                b: 0,
+            },
+            Parameters { a: 0, b: 1_000_000 },
+            Parameters {
+                a: WAD / 1_000_000,
+                b: WAD / 1_000,
+            },
+        ];
+        let supplies = [
+            0u128,
+            1_000,
+            WAD,
+            1_000 * WAD,
+            1_000_000_000 * WAD,
+            1_000_000_000_000_000 * WAD,
+        ];
+        let amounts = [1_000_000u128, WAD, 1_000 * WAD, 1_000_000 * WAD];
+
+        for parameters in all_parameters.iter() {
+            for &supply in supplies.iter() {
+                for &amount in amounts.iter() {
+                    let minted = LinearMixedBondingSwap::calculate_mint_amount_from_bonding_curve(
+                        amount,
+                        supply,
+                        parameters.clone(),
+                    );
+                    let (minted_amount, _) = match minted {
+                        Ok(minted) => minted,
+                        // Too small to buy a single unit at this price
+                        Err(CurveError::ZeroOutput) => continue,
+                        Err(err) => panic!("unexpected error {:?}", err),
+                    };
+                    let new_supply = supply + minted_amount;
+
+                    let amount_return =
+                        match LinearMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
+                            minted_amount,
+                            new_supply,
+                            parameters.clone(),
+                        ) {
+                            Ok((_, amount_return)) => amount_return,
+                            Err(CurveError::ZeroOutput) => 0,
+                            Err(err) => panic!("unexpected error {:?}", err),
+                        };
+                    let price =
+                        LinearMixedBondingSwap::price(new_supply, parameters.clone()).unwrap();
+
+                    // Never pays out more than was paid in, and loses at most the
+                    // value of the unit that was rounded away plus the square
+                    // root rounding of the mint
+                    assert!(amount_return <= amount);
+                    assert!(
+                        amount - amount_return <= price / WAD + amount / 1_000_000_000_000_000 + 3,
+                        "{:?} supply {} amount {} returned {}",
+                        parameters,
+                        supply,
+                        amount,
+                        amount_return
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_cost_to_mint_exact() {
+        let parameters = Parameters { a: 2, b: 3 };
+
+        let (token_amount, cost) =
+            LinearMixedBondingSwap::cost_to_mint_exact(50 * WAD, 50 * WAD, parameters).unwrap();
+
+        // Mirror of the burn test: 2 * (100^2 - 50^2) / 2 + 3 * 50
+        assert_eq!(cost, 7_650);
+        assert_eq!(token_amount, 50 * WAD);
+    }
+
+    #[test]
+    fn test_exact_output_round_trip_across_supply_range() {
+        let all_parameters = [
+            Parameters {
+                a: 1_000_000_000,
+                b: 1,
+            },
+            Parameters {
+                a: 2_000,
+                b: 3_000_000,
+            },
+            Parameters {
+                a: 1_000_000_000,
+                b: 0,
+            },
+            Parameters { a: 0, b: 1_000_000 },
+            Parameters {
+                a: WAD / 1_000_000,
+                b: WAD / 1_000,
+            },
+        ];
+        let supplies = [
+            WAD,
+            1_000 * WAD,
+            1_000_000_000 * WAD,
+            1_000_000_000_000_000 * WAD,
+        ];
+        let amounts = [1_000_000u128, WAD, 1_000 * WAD, 1_000_000 * WAD];
+
+        for parameters in all_parameters.iter() {
+            for &supply in supplies.iter() {
+                for &amount in amounts.iter() {
+                    // Paying the quoted cost mints at least the requested amount
+                    let (_, cost) = LinearMixedBondingSwap::cost_to_mint_exact(
+                        amount,
+                        supply,
+                        parameters.clone(),
+                    )
+                    .unwrap();
+                    let (minted_amount, _) =
+                        LinearMixedBondingSwap::calculate_mint_amount_from_bonding_curve(
+                            cost,
+                            supply,
+                            parameters.clone(),
+                        )
+                        .unwrap();
+                    assert!(
+                        minted_amount >= amount,
+                        "{:?} supply {} amount {} minted {}",
+                        parameters,
+                        supply,
+                        amount,
+                        minted_amount
+                    );
+
+                    // Burning the quoted amount returns the requested proceeds, give or
+                    // take the wei the forward burn rounds off each of its two terms
+                    let token_amount = match LinearMixedBondingSwap::tokens_to_burn_for_exact_out(
+                        amount,
+                        supply,
+                        parameters.clone(),
+                    ) {
+                        Ok((token_amount, _)) => token_amount,
+                        // The reserve does not hold that much
+                        Err(CurveError::InsufficientSupply) => continue,
+                        Err(err) => panic!("unexpected error {:?}", err),
+                    };
+                    let (_, burn_return) =
+                        LinearMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
+                            token_amount,
+                            supply,
+                            parameters.clone(),
+                        )
+                        .unwrap();
+                    assert!(
+                        burn_return + 1 >= amount,
+                        "{:?} supply {} amount {} returned {}",
+                        parameters,
+                        supply,
+                        amount,
+                        burn_return
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_exact_output_errors() {
+        let parameters = Parameters { a: 2, b: 3 };
+
+        assert_eq!(
+            LinearMixedBondingSwap::cost_to_mint_exact(0, 100, parameters.clone()),
+            Err(CurveError::ZeroOutput)
+        );
+        assert_eq!(
+            LinearMixedBondingSwap::tokens_to_burn_for_exact_out(0, 100, parameters.clone()),
+            Err(CurveError::ZeroOutput)
+        );
+        // The reserve at 100 tokens is 2 * 100^2 / 2 + 3 * 100 = 10_300
+        assert_eq!(
+            LinearMixedBondingSwap::tokens_to_burn_for_exact_out(
+                10_300,
+                100 * WAD,
+                parameters.clone()
+            ),
+            Ok((100 * WAD, 10_300))
+        );
+        assert_eq!(
+            LinearMixedBondingSwap::tokens_to_burn_for_exact_out(10_301, 100 * WAD, parameters),
+            Err(CurveError::InsufficientSupply)
+        );
+    }
 }
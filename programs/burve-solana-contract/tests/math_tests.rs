@@ -0,0 +1,356 @@
+// tests/math_tests.rs
+use burve_solana_contract::calculations::math::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // |actual - expected| <= expected * 1e-17 + 10 wei
+    fn assert_exp_close(actual: u128, expected: u128) {
+        let diff = actual.abs_diff(expected);
+        assert!(
+            diff <= expected / 100_000_000_000_000_000 + 10,
+            "actual {} expected {}",
+            actual,
+            expected
+        );
+    }
+
+    fn assert_ln_close(actual: i128, expected: i128) {
+        assert!(
+            actual.abs_diff(expected) <= 100,
+            "actual {} expected {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_exp_wad_known_values() {
+        assert_eq!(exp_wad(0), Some(WAD));
+        assert_exp_close(exp_wad(WAD as i128).unwrap(), 2_718_281_828_459_045_235);
+        assert_exp_close(exp_wad(-(WAD as i128)).unwrap(), 367_879_441_171_442_321);
+        assert_exp_close(exp_wad(WAD as i128 / 2).unwrap(), 1_648_721_270_700_128_146);
+        assert_exp_close(
+            exp_wad(10 * WAD as i128).unwrap(),
+            22_026_465_794_806_716_516_957,
+        );
+        assert_exp_close(
+            exp_wad(40 * WAD as i128).unwrap(),
+            235_385_266_837_019_985_407_899_910_749_034_804,
+        );
+        assert_exp_close(exp_wad(-20 * WAD as i128).unwrap(), 2_061_153_622);
+        assert_exp_close(exp_wad(1_000_000_000).unwrap(), 1_000_000_001_000_000_000);
+    }
+
+    #[test]
+    fn test_exp_wad_bounds() {
+        assert_eq!(exp_wad(MIN_EXP_INPUT - 1), Some(0));
+        assert!(exp_wad(MAX_EXP_INPUT).is_some());
+        assert_eq!(exp_wad(MAX_EXP_INPUT + 1), None);
+        assert_eq!(exp_wad(i128::MAX), None);
+    }
+
+    #[test]
+    fn test_exp_wad_is_monotonic() {
+        let mut previous = 0u128;
+        let mut x = -30 * WAD as i128;
+        while x < 45 * WAD as i128 {
+            let value = exp_wad(x).unwrap();
+            assert!(value >= previous);
+            previous = value;
+            x += 98_765_432_109_876_543;
+        }
+    }
+
+    #[test]
+    fn test_ln_wad_known_values() {
+        assert_eq!(ln_wad(WAD), Some(0));
+        assert_eq!(ln_wad(0), None);
+        assert_ln_close(ln_wad(2 * WAD).unwrap(), 693_147_180_559_945_309);
+        assert_ln_close(ln_wad(10 * WAD).unwrap(), 2_302_585_092_994_045_684);
+        assert_ln_close(ln_wad(WAD / 2).unwrap(), -693_147_180_559_945_309);
+        assert_ln_close(
+            ln_wad(3_500_000_000_000_000_000).unwrap(),
+            1_252_762_968_495_367_995,
+        );
+        assert_ln_close(ln_wad(1_000_000_000).unwrap(), -20_723_265_836_946_411_156);
+        assert_ln_close(ln_wad(100 * WAD * WAD).unwrap(), 46_051_701_859_880_913_680);
+    }
+
+    #[test]
+    fn test_ln_wad_inverts_exp_wad() {
+        let mut x = -40 * WAD as i128;
+        while x < 47 * WAD as i128 {
+            let y = exp_wad(x).unwrap();
+            let ln_y = ln_wad(y).unwrap();
+            // exp loses precision for tiny results, so only check where y >= 1e-6
+            if y >= 1_000_000_000_000 {
+                assert!(ln_y.abs_diff(x) <= 1_000_000, "x {} ln(exp(x)) {}", x, ln_y);
+            }
+            x += 123_456_789_123_456_789;
+        }
+    }
+
+    // |actual - expected| <= expected * tolerance / 1e18 + 10 wei
+    fn assert_relative_close(actual: u128, expected: u128, tolerance: u128) {
+        let diff = actual.abs_diff(expected);
+        assert!(
+            diff <= expected / WAD * tolerance + expected % WAD * tolerance / WAD + 10,
+            "actual {} expected {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_mul_div() {
+        assert_eq!(mul_div(6, 7, 4, Rounding::Down), Some(10));
+        assert_eq!(mul_div(6, 7, 4, Rounding::Up), Some(11));
+        assert_eq!(mul_div(6, 8, 4, Rounding::Down), Some(12));
+        assert_eq!(mul_div(6, 8, 4, Rounding::Up), Some(12));
+        assert_eq!(mul_div(0, 8, 4, Rounding::Up), Some(0));
+        assert_eq!(mul_div(1, 1, 0, Rounding::Down), None);
+        assert_eq!(mul_div(1, 1, 0, Rounding::Up), None);
+    }
+
+    #[test]
+    fn test_mul_div_wide_product() {
+        assert_eq!(
+            mul_div(u128::MAX, u128::MAX, u128::MAX, Rounding::Down),
+            Some(u128::MAX)
+        );
+        assert_eq!(
+            mul_div(u128::MAX, u128::MAX, u128::MAX, Rounding::Up),
+            Some(u128::MAX)
+        );
+        assert_eq!(mul_div(u128::MAX, 2, 1, Rounding::Down), None);
+        assert_eq!(
+            mul_div(u128::MAX / 3, 9, 3, Rounding::Down),
+            Some(u128::MAX / 3 * 3)
+        );
+        // (2^127 + 1) * 4 / 8 = 2^126 + 0.5
+        let a = (1u128 << 127) + 1;
+        assert_eq!(mul_div(a, 4, 8, Rounding::Down), Some(1u128 << 126));
+        assert_eq!(mul_div(a, 4, 8, Rounding::Up), Some((1u128 << 126) + 1));
+        // Rounding up past u128::MAX overflows
+        assert_eq!(mul_div(u128::MAX, 3, 2, Rounding::Up), None);
+        assert_eq!(
+            mul_div(u128::MAX, u128::MAX - 1, u128::MAX, Rounding::Up),
+            Some(u128::MAX - 1)
+        );
+    }
+
+    #[test]
+    fn test_mul_mul_div() {
+        assert_eq!(mul_mul_div(2, 3, 7, 4, Rounding::Down), Some(10));
+        assert_eq!(mul_mul_div(2, 3, 7, 4, Rounding::Up), Some(11));
+        assert_eq!(mul_mul_div(1, 1, 1, 0, Rounding::Down), None);
+        // 2^127 * 2^127 * 3 / 2^128 needs a 384-bit intermediate
+        let half = 1u128 << 127;
+        assert_eq!(
+            mul_mul_div(half, half, 3, u128::MAX, Rounding::Down),
+            mul_div(half, 3, 2, Rounding::Down)
+        );
+        assert_eq!(
+            mul_mul_div(half, half, 2, 1u128 << 126, Rounding::Down),
+            None
+        );
+        assert_eq!(
+            mul_mul_div(WAD * WAD, WAD, 3, WAD * WAD, Rounding::Up),
+            Some(3 * WAD)
+        );
+    }
+
+    #[test]
+    fn test_mul_div_rounding_is_consistent() {
+        for a in 0..60u128 {
+            for b in 0..60u128 {
+                for denominator in 1..25u128 {
+                    let down = mul_div(a, b, denominator, Rounding::Down).unwrap();
+                    let up = mul_div(a, b, denominator, Rounding::Up).unwrap();
+                    assert!(down * denominator <= a * b);
+                    assert!(up * denominator >= a * b);
+                    assert!(up - down <= 1);
+                    assert_eq!(up == down, a * b % denominator == 0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_mul_wad_and_div_wad() {
+        let one_and_half = 1_500_000_000_000_000_000;
+        assert_eq!(
+            mul_wad(one_and_half, 2 * WAD, Rounding::Down),
+            Some(3 * WAD)
+        );
+        assert_eq!(
+            div_wad(3 * WAD, 2 * WAD, Rounding::Down),
+            Some(one_and_half)
+        );
+        // 1 / 3
+        assert_eq!(
+            div_wad(WAD, 3 * WAD, Rounding::Down),
+            Some(333_333_333_333_333_333)
+        );
+        assert_eq!(
+            div_wad(WAD, 3 * WAD, Rounding::Up),
+            Some(333_333_333_333_333_334)
+        );
+        // 1 wei * 0.5
+        assert_eq!(mul_wad(1, WAD / 2, Rounding::Down), Some(0));
+        assert_eq!(mul_wad(1, WAD / 2, Rounding::Up), Some(1));
+        assert_eq!(div_wad(WAD, 0, Rounding::Down), None);
+        assert_eq!(mul_wad(u128::MAX, 2 * WAD, Rounding::Down), None);
+    }
+
+    #[test]
+    fn test_sqrt_exhaustive() {
+        for x in 0..200_000u128 {
+            let down = sqrt(x, Rounding::Down);
+            let up = sqrt(x, Rounding::Up);
+            assert!(down * down <= x && (down + 1) * (down + 1) > x);
+            assert!(up * up >= x && (up == 0 || (up - 1) * (up - 1) < x));
+        }
+    }
+
+    #[test]
+    fn test_sqrt_large_values() {
+        assert_eq!(sqrt(u128::MAX, Rounding::Down), u64::MAX as u128);
+        assert_eq!(sqrt(u128::MAX, Rounding::Up), u64::MAX as u128 + 1);
+        let root = 12_345_678_901_234_567u128;
+        assert_eq!(sqrt(root * root, Rounding::Down), root);
+        assert_eq!(sqrt(root * root, Rounding::Up), root);
+        assert_eq!(sqrt(root * root - 1, Rounding::Down), root - 1);
+        assert_eq!(sqrt(root * root + 1, Rounding::Up), root + 1);
+    }
+
+    #[test]
+    fn test_sqrt_mul() {
+        assert_eq!(sqrt_mul(u128::MAX, u128::MAX, Rounding::Down), u128::MAX);
+        assert_eq!(sqrt_mul(u128::MAX, u128::MAX, Rounding::Up), u128::MAX);
+        assert_eq!(sqrt_mul(1 << 100, 1 << 100, Rounding::Down), 1 << 100);
+        assert_eq!(sqrt_mul(1 << 100, (1 << 100) + 1, Rounding::Down), 1 << 100);
+        assert_eq!(
+            sqrt_mul(1 << 100, (1 << 100) + 1, Rounding::Up),
+            (1 << 100) + 1
+        );
+        assert_eq!(sqrt_mul(7, 7, Rounding::Up), 7);
+    }
+
+    #[test]
+    fn test_sqrt_sum_of_products() {
+        // 3^2 + 4^2 = 5^2
+        assert_eq!(sqrt_sum_of_products(3, 3, 4, 4, Rounding::Down), Some(5));
+        assert_eq!(sqrt_sum_of_products(3, 3, 4, 5, Rounding::Down), Some(5));
+        assert_eq!(sqrt_sum_of_products(3, 3, 4, 5, Rounding::Up), Some(6));
+        // (3 * 2^100)^2 + (4 * 2^100)^2 = (5 * 2^100)^2
+        let (x, y) = (3u128 << 100, 4u128 << 100);
+        assert_eq!(
+            sqrt_sum_of_products(x, x, y, y, Rounding::Up),
+            Some(5u128 << 100)
+        );
+        assert_eq!(
+            sqrt_sum_of_products(x, x, y, y + 1, Rounding::Down),
+            Some(5u128 << 100)
+        );
+        assert_eq!(
+            sqrt_sum_of_products(x, x, y, y + 1, Rounding::Up),
+            Some((5u128 << 100) + 1)
+        );
+        assert_eq!(
+            sqrt_sum_of_products(u128::MAX, u128::MAX, u128::MAX, u128::MAX, Rounding::Down),
+            None
+        );
+    }
+
+    #[test]
+    fn test_sqrt_diff_of_products() {
+        // 5^2 - 4^2 = 3^2
+        assert_eq!(sqrt_diff_of_products(5, 5, 4, 4, Rounding::Down), 3);
+        assert_eq!(sqrt_diff_of_products(5, 5, 3, 5, Rounding::Down), 3);
+        assert_eq!(sqrt_diff_of_products(5, 5, 3, 5, Rounding::Up), 4);
+        // A negative difference saturates
+        assert_eq!(sqrt_diff_of_products(4, 4, 5, 5, Rounding::Up), 0);
+        // (5 * 2^100)^2 - (4 * 2^100)^2 = (3 * 2^100)^2
+        let (x, y) = (5u128 << 100, 4u128 << 100);
+        assert_eq!(
+            sqrt_diff_of_products(x, x, y, y, Rounding::Up),
+            3u128 << 100
+        );
+        assert_eq!(
+            sqrt_diff_of_products(x, x, y, y + 1, Rounding::Down),
+            (3u128 << 100) - 1
+        );
+        assert_eq!(sqrt_diff_of_products(y, y, x, x, Rounding::Down), 0);
+        assert_eq!(
+            sqrt_diff_of_products(u128::MAX, u128::MAX, 0, 0, Rounding::Down),
+            u128::MAX
+        );
+    }
+
+    #[test]
+    fn test_sqrt_wad() {
+        assert_eq!(sqrt_wad(4 * WAD, Rounding::Down), 2 * WAD);
+        assert_eq!(sqrt_wad(0, Rounding::Up), 0);
+        assert_eq!(sqrt_wad(2 * WAD, Rounding::Down), 1_414_213_562_373_095_048);
+        assert_eq!(sqrt_wad(2 * WAD, Rounding::Up), 1_414_213_562_373_095_049);
+        assert_eq!(sqrt_wad(WAD / 2, Rounding::Down), 707_106_781_186_547_524);
+    }
+
+    #[test]
+    fn test_pow_wad_int() {
+        assert_eq!(pow_wad_int(2 * WAD, 0, Rounding::Down), Some(WAD));
+        assert_eq!(pow_wad_int(2 * WAD, 10, Rounding::Down), Some(1024 * WAD));
+        assert_eq!(pow_wad_int(0, 3, Rounding::Down), Some(0));
+        assert_eq!(pow_wad_int(WAD / 2, 2, Rounding::Down), Some(WAD / 4));
+        assert_eq!(pow_wad_int(10 * WAD, 21, Rounding::Down), None);
+        // 1.0001 ^ 365
+        let down = pow_wad_int(1_000_100_000_000_000_000, 365, Rounding::Down).unwrap();
+        let up = pow_wad_int(1_000_100_000_000_000_000, 365, Rounding::Up).unwrap();
+        assert!(down <= 1_037_172_411_302_551_929 && up >= 1_037_172_411_302_551_930);
+        assert!(up - down <= 1_000);
+    }
+
+    #[test]
+    fn test_pow_wad() {
+        let tolerance = 1_000; // 1e-15
+        assert_eq!(pow_wad(5 * WAD, 0), Some(WAD));
+        assert_eq!(pow_wad(0, WAD), Some(0));
+        assert_eq!(pow_wad(5 * WAD, WAD), Some(5 * WAD));
+        assert_relative_close(
+            pow_wad(2 * WAD, WAD / 2).unwrap(),
+            1_414_213_562_373_095_048,
+            tolerance,
+        );
+        assert_relative_close(pow_wad(10 * WAD, 3 * WAD).unwrap(), 1_000 * WAD, tolerance);
+        assert_relative_close(
+            pow_wad(1_500_000_000_000_000_000, 2_500_000_000_000_000_000).unwrap(),
+            2_755_675_960_631_075_360,
+            tolerance,
+        );
+        assert_relative_close(
+            pow_wad(300_000_000_000_000_000, 1_700_000_000_000_000_000).unwrap(),
+            129_153_486_074_980_267,
+            tolerance,
+        );
+        assert_relative_close(
+            pow_wad(1_000_100_000_000_000_000, 365 * WAD).unwrap(),
+            1_037_172_411_302_551_929,
+            tolerance,
+        );
+        assert_eq!(pow_wad(1_000 * WAD, 10 * WAD), None);
+    }
+
+    #[test]
+    fn test_pow_wad_matches_pow_wad_int() {
+        for base in [WAD / 3, WAD, 1_234_567_890_123_456_789, 7 * WAD] {
+            for n in 0..8u32 {
+                let exact = pow_wad_int(base, n, Rounding::Down).unwrap();
+                let approx = pow_wad(base, n as u128 * WAD).unwrap();
+                assert_relative_close(approx, exact, 10_000);
+            }
+        }
+    }
+}
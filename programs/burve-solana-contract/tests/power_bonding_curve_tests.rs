@@ -0,0 +1,218 @@
+// tests/power_bonding_curve_tests.rs
+use burve_solana_contract::calculations::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Price of 0.001 for the first whole token, reserve ratio of 50%
+    fn setup() -> Parameters {
+        Parameters {
+            a: WAD / 1_000,
+            b: WAD / 2,
+        }
+    }
+
+    #[test]
+    fn test_price_from_power_bonding_curve() {
+        let parameters = setup();
+
+        // price(x) = 0.001 * x with a reserve ratio of 50%
+        let price = PowerMixedBondingSwap::price(1_000 * WAD, parameters.clone()).unwrap();
+        assert!(price.abs_diff(WAD) <= WAD / 1_000_000_000_000);
+        assert_eq!(PowerMixedBondingSwap::price(0, parameters), Ok(0));
+    }
+
+    #[test]
+    fn test_constant_price_with_full_reserve_ratio() {
+        let parameters = Parameters {
+            a: WAD / 1_000,
+            b: WAD,
+        };
+
+        let (token_amount, _) = PowerMixedBondingSwap::calculate_mint_amount_from_bonding_curve(
+            WAD,
+            1_000 * WAD,
+            parameters.clone(),
+        )
+        .unwrap();
+        assert!(token_amount <= 1_000 * WAD);
+        assert!(1_000 * WAD - token_amount <= WAD / 1_000_000_000_000);
+        assert_eq!(
+            PowerMixedBondingSwap::price(1_000 * WAD, parameters),
+            Ok(WAD / 1_000)
+        );
+    }
+
+    #[test]
+    fn test_calculate_burn_amount_from_power_bonding_curve() {
+        let parameters = setup();
+
+        let (token_amount, raising_token_amount) =
+            PowerMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
+                1_000 * WAD,
+                2_000 * WAD,
+                parameters,
+            )
+            .unwrap();
+
+        // reserve(x) = 0.0005 * x^2, so 0.0005 * (2000^2 - 1000^2) = 1500
+        assert_eq!(token_amount, 1_000 * WAD);
+        assert!(raising_token_amount <= 1_500 * WAD);
+        assert!(1_500 * WAD - raising_token_amount <= WAD / 100_000_000_000);
+    }
+
+    #[test]
+    fn test_mint_burn_round_trip_across_supply_range() {
+        let all_parameters = [
+            setup(),
+            Parameters {
+                a: WAD / 1_000,
+                b: WAD,
+            },
+            Parameters {
+                a: WAD / 1_000_000,
+                b: WAD / 3,
+            },
+            Parameters {
+                a: WAD / 1_000_000_000_000,
+                b: MIN_RESERVE_RATIO * 20,
+            },
+        ];
+        let supplies = [0u128, WAD, 1_000 * WAD, 1_000_000 * WAD];
+        let amounts = [WAD / 1_000, WAD, 1_000 * WAD];
+
+        for parameters in all_parameters.iter() {
+            for &supply in supplies.iter() {
+                for &amount in amounts.iter() {
+                    let (minted_amount, _) =
+                        match PowerMixedBondingSwap::calculate_mint_amount_from_bonding_curve(
+                            amount,
+                            supply,
+                            parameters.clone(),
+                        ) {
+                            Ok(minted) => minted,
+                            // Too small to buy anything past the error margin
+                            Err(CurveError::ZeroOutput) => continue,
+                            Err(err) => panic!("unexpected error {:?}", err),
+                        };
+
+                    let amount_return =
+                        match PowerMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
+                            minted_amount,
+                            supply + minted_amount,
+                            parameters.clone(),
+                        ) {
+                            Ok((_, amount_return)) => amount_return,
+                            Err(CurveError::ZeroOutput) => 0,
+                            Err(err) => panic!("unexpected error {:?}", err),
+                        };
+
+                    // Never pays out more than was paid in
+                    assert!(
+                        amount_return <= amount,
+                        "{:?} supply {} amount {} returned {}",
+                        parameters,
+                        supply,
+                        amount,
+                        amount_return
+                    );
+                }
+            }
+        }
+    }
+
+    // The quotes keep a margin for the error of `pow_wad`, relative to the whole
+    // reserve rather than to the trade
+    fn error_margin(token_current_supply: u128, parameters: &Parameters) -> u128 {
+        let (_, reserve) = PowerMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
+            token_current_supply,
+            token_current_supply,
+            parameters.clone(),
+        )
+        .unwrap();
+        reserve / 100_000_000_000_000 + 1_000_000
+    }
+
+    #[test]
+    fn test_exact_output_round_trip() {
+        let parameters = setup();
+        let supplies = [WAD, 1_000 * WAD, 1_000_000 * WAD];
+        let amounts = [WAD / 1_000, WAD, 1_000 * WAD];
+
+        for &supply in supplies.iter() {
+            for &amount in amounts.iter() {
+                // The quoted cost is never below what burning the tokens returns
+                let (_, cost) =
+                    PowerMixedBondingSwap::cost_to_mint_exact(amount, supply, parameters.clone())
+                        .unwrap();
+                let (_, burn_return) =
+                    PowerMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
+                        amount,
+                        supply + amount,
+                        parameters.clone(),
+                    )
+                    .unwrap();
+                assert!(cost >= burn_return);
+                assert!(cost - burn_return <= error_margin(supply + amount, &parameters));
+
+                // Minting the quoted burn back costs at least the requested proceeds
+                let token_amount = match PowerMixedBondingSwap::tokens_to_burn_for_exact_out(
+                    amount,
+                    supply,
+                    parameters.clone(),
+                ) {
+                    Ok((token_amount, _)) => token_amount,
+                    // The reserve does not hold that much
+                    Err(CurveError::InsufficientSupply) => continue,
+                    Err(err) => panic!("unexpected error {:?}", err),
+                };
+                let (_, mint_cost) = PowerMixedBondingSwap::cost_to_mint_exact(
+                    token_amount,
+                    supply - token_amount,
+                    parameters.clone(),
+                )
+                .unwrap();
+                assert!(token_amount <= supply);
+                assert!(mint_cost >= amount);
+                assert!(mint_cost - amount <= error_margin(supply, &parameters));
+            }
+        }
+    }
+
+    #[test]
+    fn test_power_bonding_curve_errors() {
+        let parameters = setup();
+
+        assert_eq!(
+            PowerMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
+                WAD + 1,
+                WAD,
+                parameters.clone(),
+            ),
+            Err(CurveError::InsufficientSupply)
+        );
+        assert_eq!(
+            PowerMixedBondingSwap::calculate_mint_amount_from_bonding_curve(
+                0,
+                WAD,
+                parameters.clone(),
+            ),
+            Err(CurveError::ZeroOutput)
+        );
+        assert_eq!(
+            PowerMixedBondingSwap::tokens_to_burn_for_exact_out(WAD, WAD, parameters),
+            Err(CurveError::InsufficientSupply)
+        );
+        for b in [0, MIN_RESERVE_RATIO - 1, WAD + 1] {
+            assert_eq!(
+                PowerMixedBondingSwap::price(WAD, Parameters { a: WAD, b }),
+                Err(CurveError::InvalidParameters)
+            );
+        }
+        assert_eq!(
+            PowerMixedBondingSwap::price(WAD, Parameters { a: 0, b: WAD }),
+            Err(CurveError::InvalidParameters)
+        );
+    }
+}
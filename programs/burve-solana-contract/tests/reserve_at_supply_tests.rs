@@ -0,0 +1,174 @@
+// tests/reserve_at_supply_tests.rs
+use burve_solana_contract::calculations::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SUPPLIES: [u128; 6] = [
+        0,
+        WAD,
+        1_000 * WAD,
+        100_000 * WAD,
+        1_000_000 * WAD,
+        5_000_000 * WAD,
+    ];
+
+    // reserve(s2) - reserve(s1) matches burning s2 - s1 at s2, give or take
+    // `tolerance`, and a burn never pays out more than the reserve drops by,
+    // give or take the `rounding` of flooring the burn's terms separately
+    fn assert_reserve_consistent<C: BondingCurve>(
+        params: C::Params,
+        rounding: u128,
+        tolerance: u128,
+    ) where
+        C::Params: Clone,
+    {
+        for (i, &s1) in SUPPLIES.iter().enumerate() {
+            for &s2 in SUPPLIES[i + 1..].iter() {
+                let reserve1 = C::reserve_at_supply(s1, params.clone()).unwrap();
+                let reserve2 = C::reserve_at_supply(s2, params.clone()).unwrap();
+                let (_, burn_return) =
+                    C::calculate_burn_amount_from_bonding_curve(s2 - s1, s2, params.clone())
+                        .unwrap();
+
+                assert!(reserve2 >= reserve1);
+                assert!(
+                    burn_return <= reserve2 - reserve1 + rounding,
+                    "burning {} at {} returned {} from a reserve drop of {}",
+                    s2 - s1,
+                    s2,
+                    burn_return,
+                    reserve2 - reserve1
+                );
+                assert!(
+                    (reserve2 - reserve1).abs_diff(burn_return) <= tolerance,
+                    "burning {} at {} returned {} from a reserve drop of {}",
+                    s2 - s1,
+                    s2,
+                    burn_return,
+                    reserve2 - reserve1
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_linear_reserve_matches_burns() {
+        let parameters = Parameters {
+            a: 1_000_000_000,
+            b: WAD / 1_000,
+        };
+        assert_reserve_consistent::<LinearMixedBondingSwap>(parameters.clone(), 2, 2);
+
+        // reserve(x) = k * x^2 / 2 + p * x
+        assert_eq!(
+            LinearMixedBondingSwap::reserve_at_supply(1_000_000 * WAD, parameters),
+            Ok(1_500 * WAD)
+        );
+    }
+
+    #[test]
+    fn test_exp_reserve_matches_burns() {
+        let parameters = Parameters {
+            a: WAD / 1_000,
+            b: 1_000_000 * WAD,
+        };
+        // Flooring e ^ (x / b) * a before scaling by b loses up to b / WAD
+        let rounding = parameters.b / WAD + 2;
+        assert_reserve_consistent::<ExpMixedBondingSwap>(parameters, rounding, rounding);
+    }
+
+    #[test]
+    fn test_power_reserve_matches_burns() {
+        let parameters = Parameters {
+            a: WAD / 1_000,
+            b: WAD / 2,
+        };
+        // The burn keeps the error margin of `pow_wad` around both ends
+        let (_, tolerance) = PowerMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
+            5_000_000 * WAD,
+            5_000_000 * WAD,
+            parameters.clone(),
+        )
+        .unwrap();
+        assert_reserve_consistent::<PowerMixedBondingSwap>(
+            parameters,
+            0,
+            tolerance / 100_000_000_000_000 + 1_000_000,
+        );
+    }
+
+    #[test]
+    fn test_constant_product_reserve_matches_burns() {
+        let parameters = Parameters {
+            a: 1_073_000_000 * WAD,
+            b: 30 * WAD,
+        };
+        assert_reserve_consistent::<ConstantProductMixedBondingSwap>(parameters, 2, 2);
+    }
+
+    #[test]
+    fn test_stepped_reserve_matches_burns() {
+        let tiers = vec![(1_000_000 * WAD, WAD / 1_000), (6_000_000 * WAD, WAD / 500)];
+        assert_reserve_consistent::<SteppedMixedBondingSwap>(tiers.clone(), 2, 2);
+
+        assert_eq!(
+            SteppedMixedBondingSwap::reserve_at_supply(5_000_000 * WAD, tiers),
+            Ok(9_000 * WAD)
+        );
+    }
+
+    #[test]
+    fn test_sigmoid_reserve_matches_burns() {
+        let parameters = SigmoidParameters {
+            max_price: WAD / 100,
+            midpoint_supply: 5_000_000 * WAD,
+            steepness: WAD / 1_000_000,
+        };
+        // The burn keeps the error margin of the logarithms around both ends
+        let tolerance = parameters.max_price / parameters.steepness * 10_000;
+        assert_reserve_consistent::<SigmoidMixedBondingSwap>(parameters, 0, tolerance);
+    }
+
+    #[test]
+    fn test_market_cap_and_average_price() {
+        let parameters = Parameters {
+            a: 1_000_000_000,
+            b: WAD / 1_000,
+        };
+
+        // price(1M) = 0.002
+        assert_eq!(
+            LinearMixedBondingSwap::market_cap(1_000_000 * WAD, parameters.clone()),
+            Ok(2_000 * WAD)
+        );
+        // Minting 1M from zero costs 1_500, an average of 0.0015
+        assert_eq!(
+            LinearMixedBondingSwap::average_price(1_000_000 * WAD, 0, parameters.clone()),
+            Ok(WAD * 15 / 10_000)
+        );
+        // The average sits between the price before and after the trade
+        let average =
+            LinearMixedBondingSwap::average_price(1_000 * WAD, 1_000_000 * WAD, parameters.clone())
+                .unwrap();
+        assert!(
+            average >= LinearMixedBondingSwap::price(1_000_000 * WAD, parameters.clone()).unwrap()
+        );
+        assert!(
+            average <= LinearMixedBondingSwap::price(1_001_000 * WAD, parameters.clone()).unwrap()
+        );
+        assert_eq!(
+            LinearMixedBondingSwap::average_price(0, 0, parameters.clone()),
+            Err(CurveError::ZeroOutput)
+        );
+        assert_eq!(
+            LinearMixedBondingSwap::market_cap(0, parameters.clone()),
+            Ok(0)
+        );
+        assert_eq!(
+            LinearMixedBondingSwap::reserve_at_supply(0, parameters),
+            Ok(0)
+        );
+    }
+}
@@ -0,0 +1,74 @@
+// tests/revenue_split_tests.rs
+use burve_solana_contract::calculations::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use burve_solana_contract::{MAX_REVENUE_RECIPIENTS, MAX_TAX_RATE_DENOMINATOR};
+
+    // Creator, DAO and marketing
+    const WEIGHTS: [u16; 3] = [5_000, 3_000, 2_000];
+
+    #[test]
+    fn test_split_by_weights() {
+        assert_eq!(
+            split_by_weights(1_000_000, &WEIGHTS),
+            Ok(vec![500_000, 300_000, 200_000])
+        );
+        assert_eq!(split_by_weights(0, &WEIGHTS), Ok(vec![0, 0, 0]));
+        assert_eq!(split_by_weights(1_000, &[10_000]), Ok(vec![1_000]));
+    }
+
+    #[test]
+    fn test_split_pays_out_everything() {
+        for amount in [1u64, 7, 999, 10_001, 123_456_789, u64::MAX] {
+            for weights in [&WEIGHTS[..], &[3_333, 3_333, 3_334], &[1, 9_999]] {
+                let shares = split_by_weights(amount, weights).unwrap();
+                assert_eq!(
+                    shares.iter().map(|share| *share as u128).sum::<u128>(),
+                    amount as u128
+                );
+                // Only the last share carries the rounding dust
+                for (share, weight) in shares.iter().zip(weights).take(weights.len() - 1) {
+                    assert_eq!(
+                        *share as u128,
+                        amount as u128 * *weight as u128 / MAX_TAX_RATE_DENOMINATOR as u128
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_invalid_split() {
+        assert_eq!(
+            validate_split_weights(&[]),
+            Err(CurveError::InvalidParameters)
+        );
+        assert_eq!(
+            validate_split_weights(&[5_000, 4_999]),
+            Err(CurveError::InvalidParameters)
+        );
+        assert_eq!(
+            validate_split_weights(&[5_000, 5_001]),
+            Err(CurveError::InvalidParameters)
+        );
+        assert_eq!(
+            validate_split_weights(&[10_000, 0]),
+            Err(CurveError::InvalidParameters)
+        );
+        let too_many = vec![1; MAX_REVENUE_RECIPIENTS + 1];
+        assert_eq!(
+            validate_split_weights(&too_many),
+            Err(CurveError::InvalidParameters)
+        );
+        assert_eq!(
+            split_by_weights(1_000, &[6_000, 6_000]),
+            Err(CurveError::InvalidParameters)
+        );
+        assert_eq!(
+            validate_split_weights(&[2_500; MAX_REVENUE_RECIPIENTS]),
+            Ok(())
+        );
+    }
+}
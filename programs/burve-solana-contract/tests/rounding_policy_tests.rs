@@ -0,0 +1,219 @@
+// tests/rounding_policy_tests.rs
+use burve_solana_contract::calculations::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    const ROUNDS: usize = 20;
+    const STEPS: usize = 30;
+
+    // Mints and burns at random, then burns whatever is left, and checks the
+    // curve never paid out more than it took in
+    fn assert_no_round_trip_profit<C: BondingCurve>(params: C::Params, max_paid: u128)
+    where
+        C::Params: Clone,
+    {
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..ROUNDS {
+            let mut supply = 0u128;
+            let mut total_paid = 0u128;
+            let mut total_returned = 0u128;
+
+            for _ in 0..STEPS {
+                if supply == 0 || rng.gen_bool(0.5) {
+                    let paid = rng.gen_range(1..=max_paid);
+                    match C::calculate_mint_amount_from_bonding_curve(paid, supply, params.clone())
+                    {
+                        Ok((minted, _)) => {
+                            supply += minted;
+                            total_paid += paid;
+                        }
+                        Err(CurveError::ZeroOutput) => continue,
+                        Err(err) => panic!("unexpected error {:?}", err),
+                    }
+                } else {
+                    let burned = rng.gen_range(1..=supply);
+                    match C::calculate_burn_amount_from_bonding_curve(
+                        burned,
+                        supply,
+                        params.clone(),
+                    ) {
+                        Ok((_, returned)) => {
+                            supply -= burned;
+                            total_returned += returned;
+                        }
+                        Err(CurveError::ZeroOutput) => continue,
+                        Err(err) => panic!("unexpected error {:?}", err),
+                    }
+                }
+                assert!(
+                    total_returned <= total_paid,
+                    "returned {} of {} paid",
+                    total_returned,
+                    total_paid
+                );
+            }
+
+            if supply > 0 {
+                match C::calculate_burn_amount_from_bonding_curve(supply, supply, params.clone()) {
+                    Ok((_, returned)) => total_returned += returned,
+                    Err(CurveError::ZeroOutput) => {}
+                    Err(err) => panic!("unexpected error {:?}", err),
+                }
+            }
+            assert!(
+                total_returned <= total_paid,
+                "returned {} of {} paid",
+                total_returned,
+                total_paid
+            );
+        }
+    }
+
+    // The exact-output quotes never undercharge: paying the quoted cost mints
+    // at least the requested tokens, and burning the quoted tokens returns at
+    // least the requested proceeds
+    fn assert_exact_quotes_never_undercharge<C: BondingCurve>(
+        params: C::Params,
+        max_supply: u128,
+        max_amount: u128,
+    ) where
+        C::Params: Clone,
+    {
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..ROUNDS * STEPS {
+            let supply = rng.gen_range(0..=max_supply);
+            let token_amount = rng.gen_range(1..=max_amount);
+            let (_, cost) = C::cost_to_mint_exact(token_amount, supply, params.clone()).unwrap();
+            let (minted, _) =
+                C::calculate_mint_amount_from_bonding_curve(cost, supply, params.clone()).unwrap();
+            assert!(
+                minted >= token_amount,
+                "paying {} at {} minted {} instead of {}",
+                cost,
+                supply,
+                minted,
+                token_amount
+            );
+
+            if supply == 0 {
+                continue;
+            }
+            let (_, reserve) =
+                C::calculate_burn_amount_from_bonding_curve(supply, supply, params.clone())
+                    .unwrap_or((0, 0));
+            if reserve < 2 {
+                continue;
+            }
+            let raising_amount = rng.gen_range(1..reserve);
+            let burned =
+                match C::tokens_to_burn_for_exact_out(raising_amount, supply, params.clone()) {
+                    Ok((burned, _)) => burned,
+                    // The quote keeps a margin the reserve cannot cover
+                    Err(CurveError::InsufficientSupply) => continue,
+                    Err(err) => panic!("unexpected error {:?}", err),
+                };
+            let (_, returned) =
+                C::calculate_burn_amount_from_bonding_curve(burned, supply, params.clone())
+                    .unwrap();
+            assert!(
+                returned >= raising_amount,
+                "burning {} at {} returned {} instead of {}",
+                burned,
+                supply,
+                returned,
+                raising_amount
+            );
+        }
+    }
+
+    #[test]
+    fn test_linear_rounding_favours_protocol() {
+        let parameters = Parameters {
+            a: 1_000_000_000,
+            b: WAD / 1_000,
+        };
+        assert_no_round_trip_profit::<LinearMixedBondingSwap>(parameters.clone(), 10_000 * WAD);
+        assert_exact_quotes_never_undercharge::<LinearMixedBondingSwap>(
+            parameters,
+            10_000_000 * WAD,
+            1_000_000 * WAD,
+        );
+    }
+
+    #[test]
+    fn test_exp_rounding_favours_protocol() {
+        let parameters = Parameters {
+            a: WAD / 1_000,
+            b: 1_000_000 * WAD,
+        };
+        assert_no_round_trip_profit::<ExpMixedBondingSwap>(parameters.clone(), 10_000 * WAD);
+        assert_exact_quotes_never_undercharge::<ExpMixedBondingSwap>(
+            parameters,
+            10_000_000 * WAD,
+            1_000_000 * WAD,
+        );
+    }
+
+    #[test]
+    fn test_power_rounding_favours_protocol() {
+        let parameters = Parameters {
+            a: WAD / 1_000,
+            b: WAD / 2,
+        };
+        assert_no_round_trip_profit::<PowerMixedBondingSwap>(parameters.clone(), 10_000 * WAD);
+        assert_exact_quotes_never_undercharge::<PowerMixedBondingSwap>(
+            parameters,
+            10_000_000 * WAD,
+            1_000_000 * WAD,
+        );
+    }
+
+    #[test]
+    fn test_constant_product_rounding_favours_protocol() {
+        let parameters = Parameters {
+            a: 1_073_000_000 * WAD,
+            b: 30 * WAD,
+        };
+        assert_no_round_trip_profit::<ConstantProductMixedBondingSwap>(parameters.clone(), WAD);
+        assert_exact_quotes_never_undercharge::<ConstantProductMixedBondingSwap>(
+            parameters,
+            500_000_000 * WAD,
+            100_000_000 * WAD,
+        );
+    }
+
+    #[test]
+    fn test_stepped_rounding_favours_protocol() {
+        let tiers = vec![
+            (1_000_000 * WAD, WAD / 1_000),
+            (5_000_000 * WAD, WAD / 500),
+            (1_000_000_000 * WAD, WAD / 100),
+        ];
+        assert_no_round_trip_profit::<SteppedMixedBondingSwap>(tiers.clone(), 10_000 * WAD);
+        assert_exact_quotes_never_undercharge::<SteppedMixedBondingSwap>(
+            tiers,
+            10_000_000 * WAD,
+            1_000_000 * WAD,
+        );
+    }
+
+    #[test]
+    fn test_sigmoid_rounding_favours_protocol() {
+        let parameters = SigmoidParameters {
+            max_price: WAD / 100,
+            midpoint_supply: 5_000_000 * WAD,
+            steepness: WAD / 1_000_000,
+        };
+        assert_no_round_trip_profit::<SigmoidMixedBondingSwap>(parameters.clone(), 10_000 * WAD);
+        assert_exact_quotes_never_undercharge::<SigmoidMixedBondingSwap>(
+            parameters,
+            10_000_000 * WAD,
+            1_000_000 * WAD,
+        );
+    }
+}
@@ -0,0 +1,385 @@
+// tests/sigmoid_bonding_curve_tests.rs
+use burve_solana_contract::calculations::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    // Price flattens out at 0.01, half way there at 5M tokens, with a steepness
+    // of 1e-6 per token, so M / k = 10_000 of the raising token
+    fn setup() -> SigmoidParameters {
+        SigmoidParameters {
+            max_price: WAD / 100,
+            midpoint_supply: 5_000_000 * WAD,
+            steepness: WAD / 1_000_000,
+        }
+    }
+
+    #[test]
+    fn test_calculation() {
+        let parameters = setup();
+        let native_asset = 2_000 * WAD;
+
+        let (token_amount1, raising_token_amount1) =
+            SigmoidMixedBondingSwap::calculate_mint_amount_from_bonding_curve(
+                native_asset,
+                0,
+                parameters.clone(),
+            )
+            .unwrap();
+        let (_token_amount2, raising_token_amount2) =
+            SigmoidMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
+                token_amount1,
+                token_amount1,
+                parameters.clone(),
+            )
+            .unwrap();
+        let price = SigmoidMixedBondingSwap::price(token_amount1, parameters.clone()).unwrap();
+
+        assert_eq!(raising_token_amount1, native_asset);
+        assert!(token_amount1 > 0);
+        assert!(raising_token_amount2 <= native_asset);
+        assert!(price > 0);
+    }
+
+    #[test]
+    fn test_price_from_sigmoid_bonding_curve() {
+        let parameters = setup();
+
+        // M / (1 + e ^ 5) at zero, M / 2 at the midpoint and close to M far past it
+        let price = SigmoidMixedBondingSwap::price(0, parameters.clone()).unwrap();
+        assert!(price.abs_diff(66_928_509_242_848) <= 1_000);
+        assert_eq!(
+            SigmoidMixedBondingSwap::price(parameters.midpoint_supply, parameters.clone()),
+            Ok(WAD / 200)
+        );
+        let price = SigmoidMixedBondingSwap::price(100_000_000 * WAD, parameters.clone()).unwrap();
+        assert!(price <= parameters.max_price);
+        assert!(parameters.max_price - price <= parameters.max_price / 1_000_000_000);
+    }
+
+    #[test]
+    fn test_price_is_monotonic() {
+        let parameters = setup();
+        let mut last_price = 0;
+
+        for step in 0..=100u128 {
+            let price =
+                SigmoidMixedBondingSwap::price(step * 200_000 * WAD, parameters.clone()).unwrap();
+            assert!(price >= last_price);
+            assert!(price <= parameters.max_price);
+            last_price = price;
+        }
+    }
+
+    #[test]
+    fn test_calculate_burn_amount_from_sigmoid_bonding_curve() {
+        let parameters = setup();
+
+        // M / k * (ln(2) - ln(1 + e ^ -5)) up to the midpoint
+        let (_, raising_token_amount) =
+            SigmoidMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
+                parameters.midpoint_supply,
+                parameters.midpoint_supply,
+                parameters.clone(),
+            )
+            .unwrap();
+        assert!(raising_token_amount.abs_diff(6_864_318_320_708_272_408_008) <= 100_000_000);
+    }
+
+    #[test]
+    fn test_multi_mint() {
+        let parameters = setup();
+        let user1 = WAD;
+        let round = 100;
+        let mut supply = 0;
+
+        for _ in 0..round {
+            let (minted_amount, _raising_token_amount) =
+                SigmoidMixedBondingSwap::calculate_mint_amount_from_bonding_curve(
+                    user1,
+                    supply,
+                    parameters.clone(),
+                )
+                .unwrap();
+            assert!(minted_amount > 0);
+            supply += minted_amount;
+        }
+
+        // Never hands out more than a single mint of the total would
+        let (total_amount, _) = SigmoidMixedBondingSwap::calculate_mint_amount_from_bonding_curve(
+            user1 * round,
+            0,
+            parameters,
+        )
+        .unwrap();
+        assert!(supply <= total_amount);
+    }
+
+    #[test]
+    fn test_multi_burn() {
+        let parameters = setup();
+        let round = 100;
+
+        let (initial_minted_amount, _raising_token_amount) =
+            SigmoidMixedBondingSwap::calculate_mint_amount_from_bonding_curve(
+                500 * WAD,
+                0,
+                parameters.clone(),
+            )
+            .unwrap();
+        assert!(initial_minted_amount > 0);
+
+        let mut supply = initial_minted_amount;
+        let mut total_return = 0;
+        for _ in 0..round {
+            let (_, amount_return) =
+                SigmoidMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
+                    initial_minted_amount / round,
+                    supply,
+                    parameters.clone(),
+                )
+                .unwrap();
+            assert!(amount_return > 0);
+            supply -= initial_minted_amount / round;
+            total_return += amount_return;
+        }
+        assert!(total_return <= 500 * WAD);
+    }
+
+    #[test]
+    fn test_random_mint_and_burn() {
+        let parameters = setup();
+        let round = 100;
+
+        for _ in 0..round {
+            let mut rng = rand::thread_rng();
+            let supply = rng.gen_range(0..=20_000_000) * WAD;
+            let amount = rng.gen_range(1..=999_999) * 100_000_000_000_000_000u128; // (1..999999) * 1e17
+
+            let (minted_amount, _) =
+                SigmoidMixedBondingSwap::calculate_mint_amount_from_bonding_curve(
+                    amount,
+                    supply,
+                    parameters.clone(),
+                )
+                .unwrap();
+            assert!(minted_amount > 0);
+
+            let (_, burn_amount_return) =
+                SigmoidMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
+                    minted_amount,
+                    supply + minted_amount,
+                    parameters.clone(),
+                )
+                .unwrap();
+            assert!(burn_amount_return > 0);
+            assert!(burn_amount_return <= amount);
+        }
+    }
+
+    #[test]
+    fn test_mint_burn_round_trip() {
+        let parameters = setup();
+        let paid = WAD;
+
+        let (minted_amount, _) = SigmoidMixedBondingSwap::calculate_mint_amount_from_bonding_curve(
+            paid,
+            0,
+            parameters.clone(),
+        )
+        .unwrap();
+        let (_, amount_return) = SigmoidMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
+            minted_amount,
+            minted_amount,
+            parameters.clone(),
+        )
+        .unwrap();
+        assert!(amount_return > 0);
+        assert!(amount_return <= paid);
+    }
+
+    #[test]
+    fn test_large_supply() {
+        let parameters = setup();
+        let token_current_supply = u64::MAX as u128 * WAD;
+
+        // Past the midpoint the curve is linear at the max price and never overflows
+        let (minted_amount, _) = SigmoidMixedBondingSwap::calculate_mint_amount_from_bonding_curve(
+            WAD,
+            token_current_supply,
+            parameters.clone(),
+        )
+        .unwrap();
+        assert!(minted_amount <= 100 * WAD);
+        assert!(100 * WAD - minted_amount <= WAD / 1_000_000);
+        assert_eq!(
+            SigmoidMixedBondingSwap::price(token_current_supply, parameters),
+            Ok(WAD / 100)
+        );
+    }
+
+    #[test]
+    fn test_burn_more_than_supply() {
+        let parameters = setup();
+
+        assert_eq!(
+            SigmoidMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
+                1_000_001, 1_000_000, parameters,
+            ),
+            Err(CurveError::InsufficientSupply)
+        );
+    }
+
+    #[test]
+    fn test_zero_output() {
+        let parameters = setup();
+
+        assert_eq!(
+            SigmoidMixedBondingSwap::calculate_mint_amount_from_bonding_curve(
+                0,
+                1_000_000,
+                parameters.clone(),
+            ),
+            Err(CurveError::ZeroOutput)
+        );
+        assert_eq!(
+            SigmoidMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
+                0, 1_000_000, parameters,
+            ),
+            Err(CurveError::ZeroOutput)
+        );
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        let parameters = SigmoidParameters {
+            max_price: WAD,
+            midpoint_supply: WAD,
+            steepness: 0,
+        };
+
+        assert_eq!(
+            SigmoidMixedBondingSwap::calculate_mint_amount_from_bonding_curve(
+                1_000_000_000,
+                0,
+                parameters,
+            ),
+            Err(CurveError::InvalidParameters)
+        );
+        assert_eq!(
+            SigmoidMixedBondingSwap::price(
+                0,
+                SigmoidParameters {
+                    max_price: 0,
+                    midpoint_supply: WAD,
+                    steepness: WAD,
+                }
+            ),
+            Err(CurveError::InvalidParameters)
+        );
+    }
+
+    // The inverse quotes keep a margin for the error of the logarithms, worth a
+    // few thousand wei of the logistic times M / k
+    fn sigmoid_error_margin(parameters: &SigmoidParameters) -> u128 {
+        parameters.max_price / parameters.steepness * 10_000 + 1_000_000
+    }
+
+    #[test]
+    fn test_cost_to_mint_exact_round_trip() {
+        let parameters = setup();
+        let supplies = [0u128, WAD, 5_000_000 * WAD, 20_000_000 * WAD];
+        let token_amounts = [WAD / 1_000, WAD, 1_000 * WAD, 1_000_000 * WAD];
+
+        for &supply in supplies.iter() {
+            for &token_amount in token_amounts.iter() {
+                let (_, cost) = SigmoidMixedBondingSwap::cost_to_mint_exact(
+                    token_amount,
+                    supply,
+                    parameters.clone(),
+                )
+                .unwrap();
+                let (minted_amount, _) =
+                    SigmoidMixedBondingSwap::calculate_mint_amount_from_bonding_curve(
+                        cost,
+                        supply,
+                        parameters.clone(),
+                    )
+                    .unwrap();
+                let (_, burn_return) =
+                    SigmoidMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
+                        token_amount,
+                        supply + token_amount,
+                        parameters.clone(),
+                    )
+                    .unwrap();
+
+                // Paying the quote mints at least the requested amount, and the
+                // quote is never below what burning those tokens returns
+                assert!(minted_amount >= token_amount);
+                assert!(cost >= burn_return);
+                assert!(cost - burn_return <= sigmoid_error_margin(&parameters));
+            }
+        }
+    }
+
+    #[test]
+    fn test_tokens_to_burn_for_exact_out_round_trip() {
+        let parameters = setup();
+        let supplies = [WAD, 100_000 * WAD, 5_000_000 * WAD, 20_000_000 * WAD];
+        let raising_amounts = [1_000_000_000u128, WAD / 1_000, WAD, 1_000 * WAD];
+
+        for &supply in supplies.iter() {
+            for &raising_amount in raising_amounts.iter() {
+                let token_amount = match SigmoidMixedBondingSwap::tokens_to_burn_for_exact_out(
+                    raising_amount,
+                    supply,
+                    parameters.clone(),
+                ) {
+                    Ok((token_amount, _)) => token_amount,
+                    // The reserve does not hold that much
+                    Err(CurveError::InsufficientSupply) => continue,
+                    Err(err) => panic!("unexpected error {:?}", err),
+                };
+                let (_, burn_return) =
+                    SigmoidMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
+                        token_amount,
+                        supply,
+                        parameters.clone(),
+                    )
+                    .unwrap();
+
+                // Burning the quoted amount returns at least the requested proceeds
+                assert!(token_amount <= supply);
+                assert!(burn_return >= raising_amount);
+                assert!(burn_return - raising_amount <= sigmoid_error_margin(&parameters));
+            }
+        }
+    }
+
+    #[test]
+    fn test_exact_output_errors() {
+        let parameters = setup();
+        let (_, reserve) = SigmoidMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
+            WAD,
+            WAD,
+            parameters.clone(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            SigmoidMixedBondingSwap::cost_to_mint_exact(0, WAD, parameters.clone()),
+            Err(CurveError::ZeroOutput)
+        );
+        assert_eq!(
+            SigmoidMixedBondingSwap::tokens_to_burn_for_exact_out(0, WAD, parameters.clone()),
+            Err(CurveError::ZeroOutput)
+        );
+        assert_eq!(
+            SigmoidMixedBondingSwap::tokens_to_burn_for_exact_out(reserve * 2, WAD, parameters),
+            Err(CurveError::InsufficientSupply)
+        );
+    }
+}
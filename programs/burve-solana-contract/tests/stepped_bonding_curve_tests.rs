@@ -0,0 +1,219 @@
+// tests/stepped_bonding_curve_tests.rs
+use burve_solana_contract::calculations::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 1M tokens at 0.001, the next 5M at 0.002 and the last 4M at 0.005
+    fn setup() -> StepTiers {
+        vec![
+            (1_000_000 * WAD, WAD / 1_000),
+            (6_000_000 * WAD, WAD / 500),
+            (10_000_000 * WAD, WAD / 200),
+        ]
+    }
+
+    #[test]
+    fn test_price_from_stepped_bonding_curve() {
+        let tiers = setup();
+
+        assert_eq!(
+            SteppedMixedBondingSwap::price(0, tiers.clone()),
+            Ok(WAD / 1_000)
+        );
+        assert_eq!(
+            SteppedMixedBondingSwap::price(1_000_000 * WAD - 1, tiers.clone()),
+            Ok(WAD / 1_000)
+        );
+        assert_eq!(
+            SteppedMixedBondingSwap::price(1_000_000 * WAD, tiers.clone()),
+            Ok(WAD / 500)
+        );
+        assert_eq!(
+            SteppedMixedBondingSwap::price(10_000_000 * WAD, tiers),
+            Err(CurveError::InsufficientSupply)
+        );
+    }
+
+    #[test]
+    fn test_mint_and_burn_across_tiers() {
+        let tiers = setup();
+
+        // 500k left in the first tier for 500, then 1000 buys 500k of the second
+        let (token_amount, raising_token_amount) =
+            SteppedMixedBondingSwap::calculate_mint_amount_from_bonding_curve(
+                1_500 * WAD,
+                500_000 * WAD,
+                tiers.clone(),
+            )
+            .unwrap();
+        assert_eq!(token_amount, 1_000_000 * WAD);
+        assert_eq!(raising_token_amount, 1_500 * WAD);
+
+        // Crosses all three tiers: 1M * 0.001 + 5M * 0.002 + 1M * 0.005
+        let (_, burn_return) = SteppedMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
+            7_000_000 * WAD,
+            7_000_000 * WAD,
+            tiers.clone(),
+        )
+        .unwrap();
+        assert_eq!(burn_return, 16_000 * WAD);
+        assert_eq!(
+            SteppedMixedBondingSwap::cost_to_mint_exact(7_000_000 * WAD, 0, tiers.clone()),
+            Ok((7_000_000 * WAD, 16_000 * WAD))
+        );
+        assert_eq!(
+            SteppedMixedBondingSwap::tokens_to_burn_for_exact_out(
+                16_000 * WAD,
+                7_000_000 * WAD,
+                tiers
+            ),
+            Ok((7_000_000 * WAD, 16_000 * WAD))
+        );
+    }
+
+    #[test]
+    fn test_mint_burn_round_trip_across_supply_range() {
+        let tiers = setup();
+        let supplies = [
+            0u128,
+            WAD,
+            999_999 * WAD,
+            1_000_000 * WAD,
+            5_999_000 * WAD,
+            9_000_000 * WAD,
+        ];
+        let amounts = [1_000u128, WAD / 1_000, WAD, 1_000 * WAD];
+
+        for &supply in supplies.iter() {
+            for &amount in amounts.iter() {
+                let (minted_amount, _) =
+                    SteppedMixedBondingSwap::calculate_mint_amount_from_bonding_curve(
+                        amount,
+                        supply,
+                        tiers.clone(),
+                    )
+                    .unwrap();
+                let (_, amount_return) =
+                    SteppedMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
+                        minted_amount,
+                        supply + minted_amount,
+                        tiers.clone(),
+                    )
+                    .unwrap();
+
+                // Never pays out more than was paid in, losing a wei per tier crossed
+                assert!(amount_return <= amount);
+                assert!(amount - amount_return <= 3);
+            }
+        }
+    }
+
+    #[test]
+    fn test_exact_output_round_trip() {
+        let tiers = setup();
+        let supplies = [WAD, 999_999 * WAD, 1_000_000 * WAD, 6_500_000 * WAD];
+        let amounts = [WAD / 1_000, WAD, 1_000 * WAD];
+
+        for &supply in supplies.iter() {
+            for &amount in amounts.iter() {
+                // Paying the quoted cost mints at least the requested amount
+                let (_, cost) =
+                    SteppedMixedBondingSwap::cost_to_mint_exact(amount, supply, tiers.clone())
+                        .unwrap();
+                let (minted_amount, _) =
+                    SteppedMixedBondingSwap::calculate_mint_amount_from_bonding_curve(
+                        cost,
+                        supply,
+                        tiers.clone(),
+                    )
+                    .unwrap();
+                assert!(minted_amount >= amount);
+
+                // Burning the quoted amount returns at least the requested proceeds,
+                // give or take a wei per tier crossed
+                let token_amount = match SteppedMixedBondingSwap::tokens_to_burn_for_exact_out(
+                    amount,
+                    supply,
+                    tiers.clone(),
+                ) {
+                    Ok((token_amount, _)) => token_amount,
+                    // The reserve does not hold that much
+                    Err(CurveError::InsufficientSupply) => continue,
+                    Err(err) => panic!("unexpected error {:?}", err),
+                };
+                let (_, burn_return) =
+                    SteppedMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
+                        token_amount,
+                        supply,
+                        tiers.clone(),
+                    )
+                    .unwrap();
+                assert!(token_amount <= supply);
+                assert!(burn_return + 1 >= amount);
+            }
+        }
+    }
+
+    #[test]
+    fn test_validate_tiers() {
+        assert_eq!(SteppedMixedBondingSwap::validate_tiers(&setup()), Ok(()));
+        assert_eq!(
+            SteppedMixedBondingSwap::validate_tiers(&[]),
+            Err(CurveError::InvalidParameters)
+        );
+        // Breakpoints must strictly increase
+        assert_eq!(
+            SteppedMixedBondingSwap::validate_tiers(&[(2 * WAD, WAD), (WAD, WAD)]),
+            Err(CurveError::InvalidParameters)
+        );
+        assert_eq!(
+            SteppedMixedBondingSwap::validate_tiers(&[(WAD, WAD), (WAD, 2 * WAD)]),
+            Err(CurveError::InvalidParameters)
+        );
+        assert_eq!(
+            SteppedMixedBondingSwap::validate_tiers(&[(0, WAD)]),
+            Err(CurveError::InvalidParameters)
+        );
+        assert_eq!(
+            SteppedMixedBondingSwap::validate_tiers(&[(WAD, WAD), (2 * WAD, 0)]),
+            Err(CurveError::InvalidParameters)
+        );
+    }
+
+    #[test]
+    fn test_stepped_bonding_curve_errors() {
+        let tiers = setup();
+
+        // The last tier sells out
+        assert_eq!(
+            SteppedMixedBondingSwap::calculate_mint_amount_from_bonding_curve(
+                1_000_000 * WAD,
+                0,
+                tiers.clone(),
+            ),
+            Err(CurveError::InsufficientSupply)
+        );
+        assert_eq!(
+            SteppedMixedBondingSwap::cost_to_mint_exact(WAD, 10_000_000 * WAD - 1, tiers.clone()),
+            Err(CurveError::InsufficientSupply)
+        );
+        assert_eq!(
+            SteppedMixedBondingSwap::calculate_burn_amount_from_bonding_curve(
+                WAD + 1,
+                WAD,
+                tiers.clone(),
+            ),
+            Err(CurveError::InsufficientSupply)
+        );
+        assert_eq!(
+            SteppedMixedBondingSwap::calculate_mint_amount_from_bonding_curve(0, 0, tiers.clone()),
+            Err(CurveError::ZeroOutput)
+        );
+        assert_eq!(
+            SteppedMixedBondingSwap::tokens_to_burn_for_exact_out(WAD, WAD, tiers),
+            Err(CurveError::InsufficientSupply)
+        );
+    }
+}
@@ -0,0 +1,92 @@
+// tests/vault_solvency_tests.rs
+use burve_solana_contract::calculations::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use burve_solana_contract::{check_vault_solvency, BondingCurveType, Errors};
+
+    // 0.001 SOL per token, rising by 0.001 every 1M tokens, for a 9 decimal
+    // token raising SOL
+    fn curve() -> BondingCurveType {
+        BondingCurveType::Linear {
+            a: 1_000_000_000,
+            b: WAD / 1_000,
+        }
+    }
+
+    fn decimals() -> TokenDecimals {
+        TokenDecimals::new(9, 9).unwrap()
+    }
+
+    // Vault balance right after minting `supply` tokens from zero, at the cost
+    // the curve charges for them
+    fn minted_vault(supply: u64) -> u64 {
+        let (_, cost) =
+            curve_cost_to_mint_exact(&curve(), decimals().project_to_wad(supply), 0).unwrap();
+        decimals().raising_from_wad(cost, Rounding::Up).unwrap()
+    }
+
+    #[test]
+    fn test_vault_holding_the_reserve_is_solvent() {
+        let supply = 2_000_000_000_000_000;
+        let vault = minted_vault(supply);
+        assert_eq!(
+            check_vault_solvency(curve(), vault, 0, supply, decimals()),
+            Ok(())
+        );
+        // Fees held on top of the reserve
+        assert_eq!(
+            check_vault_solvency(curve(), vault + 5_000, 5_000, supply, decimals()),
+            Ok(())
+        );
+        assert_eq!(check_vault_solvency(curve(), 0, 0, 0, decimals()), Ok(()));
+    }
+
+    #[test]
+    fn test_burn_paying_out_too_much_is_rejected() {
+        let supply = 2_000_000_000_000_000;
+        let burned = 500_000_000_000_000;
+        let vault = minted_vault(supply);
+
+        // A burn paying out what the curve releases leaves the vault solvent
+        let (_, released) = curve_burn_amount(
+            &curve(),
+            decimals().project_to_wad(burned),
+            decimals().project_to_wad(supply),
+        )
+        .unwrap();
+        let released = decimals()
+            .raising_from_wad(released, Rounding::Down)
+            .unwrap();
+        assert_eq!(
+            check_vault_solvency(curve(), vault - released, 0, supply - burned, decimals()),
+            Ok(())
+        );
+
+        // One unit of drift more is not
+        let reserve = curve_reserve_at_supply(&curve(), decimals().project_to_wad(supply - burned))
+            .map(|reserve| decimals().raising_from_wad(reserve, Rounding::Up).unwrap())
+            .unwrap();
+        assert_eq!(
+            check_vault_solvency(curve(), reserve - 1, 0, supply - burned, decimals()),
+            Err(Errors::VaultInsolvent.into())
+        );
+    }
+
+    #[test]
+    fn test_reserved_fees_do_not_count_towards_the_reserve() {
+        let supply = 2_000_000_000_000_000;
+        let vault = minted_vault(supply);
+
+        // The fees accrued for claiming would be paid out of the reserve
+        assert_eq!(
+            check_vault_solvency(curve(), vault, 1, supply, decimals()),
+            Err(Errors::VaultInsolvent.into())
+        );
+        assert_eq!(
+            check_vault_solvency(curve(), vault, vault, supply, decimals()),
+            Err(Errors::VaultInsolvent.into())
+        );
+    }
+}
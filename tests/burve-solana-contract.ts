@@ -23,6 +23,36 @@ export function associatedAddress({
   )[0];
 }
 
+// Resolves once `promise` fails with the program error `code`
+export async function expectProgramError(
+  promise: Promise<unknown>,
+  code: string
+): Promise<void> {
+  try {
+    await promise;
+  } catch (err) {
+    expect(err.error?.errorCode?.code).to.equal(code);
+    return;
+  }
+  assert.fail(`expected ${code}`);
+}
+
+// Events the program emitted in a confirmed transaction
+export async function emittedEvents(
+  program: Program<BurveSolanaContract>,
+  signature: string
+) {
+  const tx = await program.provider.connection.getTransaction(signature, {
+    commitment: "confirmed",
+    maxSupportedTransactionVersion: 0,
+  });
+  const parser = new anchor.EventParser(
+    program.programId,
+    new anchor.BorshCoder(program.idl)
+  );
+  return Array.from(parser.parseLogs(tx.meta.logMessages));
+}
+
 describe("burve-solana-contract", () => {
   const provider = anchor.AnchorProvider.env();
   anchor.setProvider(provider);
@@ -58,6 +88,21 @@ describe("burve-solana-contract", () => {
       .rpc();
   });
 
+  it("Migrate burve base already in the current layout fails", async () => {
+    await expectProgramError(
+      program.methods
+        .migrateBurveBase()
+        .accountsStrict({
+          burveBase,
+          admin: payer.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([payer])
+        .rpc(),
+      "AccountAlreadyMigrated"
+    );
+  });
+
   const treasury = Keypair.generate();
   const [mint] = PublicKey.findProgramAddressSync(
     [
@@ -78,20 +123,28 @@ describe("burve-solana-contract", () => {
 
   it("Create new project test passes", async () => {
     const bondingCurveType = {
-      exponential: { a: new anchor.BN(10), b: new anchor.BN(10) },
+      // 0.1 SOL per token to start, growing e-fold every 100M tokens
+      exponential: {
+        a: new anchor.BN("100000000000000000"),
+        b: new anchor.BN("100000000000000000000000000"),
+      },
     };
     await program.methods
       .createNewProjectWithSol({
         name: "quick project",
         symbol: "QP",
         uri: "https://my-project-data.com/metadata.json",
+        decimals: 9,
         admin: payer.publicKey,
         treasury: treasury.publicKey,
         mintTax: 50,
         burnTax: 50,
         bondingCurveType,
+        maxSupply: null,
+        launchAuction: null,
       })
       .accountsStrict({
+        burveBase,
         projectMetadata,
         payer: payer.publicKey,
         vault,
@@ -116,12 +169,11 @@ describe("burve-solana-contract", () => {
       .mintTokenWithSol({
         amount: new anchor.BN(100),
         symbol: "QP",
-        minReceive: new anchor.BN(100),
+        order: { exactIn: { minReceive: new anchor.BN(100) } },
       })
       .accountsStrict({
         burveBase,
         projectMetadata,
-        projectTreasury: treasury.publicKey,
         from: payer.publicKey,
         mint: mint,
         vault,
@@ -129,7 +181,7 @@ describe("burve-solana-contract", () => {
           mint: mint,
           owner: payer.publicKey,
         }),
-
+        referral: null,
         systemProgram: anchor.web3.SystemProgram.programId,
         associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
         tokenProgram: TOKEN_2022_PROGRAM_ID,
@@ -138,17 +190,47 @@ describe("burve-solana-contract", () => {
       .rpc();
   });
 
+  it("Mint token below min receive fails", async () => {
+    await expectProgramError(
+      program.methods
+        .mintTokenWithSol({
+          amount: new anchor.BN(100),
+          symbol: "QP",
+          order: {
+            exactIn: { minReceive: new anchor.BN("1000000000000000000") },
+          },
+        })
+        .accountsStrict({
+          burveBase,
+          projectMetadata,
+          from: payer.publicKey,
+          mint: mint,
+          vault,
+          mintTokenAccount: associatedAddress({
+            mint: mint,
+            owner: payer.publicKey,
+          }),
+          referral: null,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([payer])
+        .rpc(),
+      "SlippageExceeded"
+    );
+  });
+
   it("Burn token test passes", async () => {
     await program.methods
       .burnTokenToSol({
         amount: new anchor.BN(10),
         symbol: "QP",
-        minReceive: new anchor.BN(10),
+        order: { exactIn: { minReceive: new anchor.BN(10) } },
       })
       .accountsStrict({
         burveBase,
         projectMetadata,
-        projectTreasury: treasury.publicKey,
         from: payer.publicKey,
         burnTokenAccount: associatedAddress({
           mint: mint,
@@ -156,11 +238,761 @@ describe("burve-solana-contract", () => {
         }),
         mint: mint,
         vault,
+        referral: null,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([payer])
+      .rpc();
+  });
+
+  it("Burn token below min receive fails", async () => {
+    await expectProgramError(
+      program.methods
+        .burnTokenToSol({
+          amount: new anchor.BN(10),
+          symbol: "QP",
+          order: {
+            exactIn: { minReceive: new anchor.BN("1000000000000000000") },
+          },
+        })
+        .accountsStrict({
+          burveBase,
+          projectMetadata,
+          from: payer.publicKey,
+          burnTokenAccount: associatedAddress({
+            mint: mint,
+            owner: payer.publicKey,
+          }),
+          mint: mint,
+          vault,
+          referral: null,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([payer])
+        .rpc(),
+      "SlippageExceeded"
+    );
+  });
+
+  it("Mint exact token test passes", async () => {
+    await program.methods
+      .mintTokenWithSol({
+        amount: new anchor.BN(10),
+        symbol: "QP",
+        order: { exactOut: { maxPay: new anchor.BN(100) } },
+      })
+      .accountsStrict({
+        burveBase,
+        projectMetadata,
+        from: payer.publicKey,
+        mint: mint,
+        vault,
+        mintTokenAccount: associatedAddress({
+          mint: mint,
+          owner: payer.publicKey,
+        }),
+        referral: null,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([payer])
+      .rpc();
+  });
+
+  it("Burn token to exact SOL test passes", async () => {
+    await program.methods
+      .burnTokenToSol({
+        amount: new anchor.BN(10),
+        symbol: "QP",
+        order: { exactOut: { maxBurn: new anchor.BN(100) } },
+      })
+      .accountsStrict({
+        burveBase,
+        projectMetadata,
+        from: payer.publicKey,
+        burnTokenAccount: associatedAddress({
+          mint: mint,
+          owner: payer.publicKey,
+        }),
+        mint: mint,
+        vault,
+        referral: null,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([payer])
+      .rpc();
+  });
+
+  it("Migrate project metadata already in the current layout fails", async () => {
+    await expectProgramError(
+      program.methods
+        .migrateProjectMetadata()
+        .accountsStrict({
+          burveBase,
+          projectMetadata,
+          mint,
+          admin: payer.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([payer])
+        .rpc(),
+      "AccountAlreadyMigrated"
+    );
+  });
+
+  const referrer = Keypair.generate();
+  const [referral] = PublicKey.findProgramAddressSync(
+    [
+      anchor.utils.bytes.utf8.encode("referral"),
+      mint.toBuffer(),
+      referrer.publicKey.toBuffer(),
+    ],
+    program.programId
+  );
+  const referredMintAccounts = (from: PublicKey) => ({
+    burveBase,
+    projectMetadata,
+    from,
+    mint: mint,
+    vault,
+    mintTokenAccount: associatedAddress({
+      mint: mint,
+      owner: from,
+    }),
+    referral,
+    systemProgram: anchor.web3.SystemProgram.programId,
+    associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+    tokenProgram: TOKEN_2022_PROGRAM_ID,
+  });
+
+  it("Airdrop referrer", async () => {
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(referrer.publicKey, 1000000000),
+      "confirmed"
+    );
+  });
+
+  it("Set burve referral share test passes", async () => {
+    // Half of the Burve fee
+    await program.methods
+      .setBurveReferralShare(5000)
+      .accountsStrict({ burveBase, admin: payer.publicKey })
+      .signers([payer])
+      .rpc();
+
+    const base = await program.account.burveBase.fetch(burveBase);
+    expect(base.referralShare).to.equal(5000);
+  });
+
+  it("Register referral test passes", async () => {
+    await program.methods
+      .registerReferral()
+      .accountsStrict({
+        referral,
+        mint,
+        referrer: referrer.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([referrer])
+      .rpc();
+
+    const balance = await program.account.referralBalance.fetch(referral);
+    expect(balance.referrer.toBase58()).to.equal(referrer.publicKey.toBase58());
+    expect(balance.mint.toBase58()).to.equal(mint.toBase58());
+    expect(balance.amount.toNumber()).to.equal(0);
+  });
+
+  it("Mint token with a referral test passes", async () => {
+    const before = await program.account.projectMetadata.fetch(projectMetadata);
+
+    await program.methods
+      .mintTokenWithSol({
+        amount: new anchor.BN(10000000),
+        symbol: "QP",
+        order: { exactIn: { minReceive: new anchor.BN(1) } },
+      })
+      .accountsStrict(referredMintAccounts(payer.publicKey))
+      .signers([payer])
+      .rpc({ commitment: "confirmed" });
+
+    const balance = await program.account.referralBalance.fetch(
+      referral,
+      "confirmed"
+    );
+    const after = await program.account.projectMetadata.fetch(
+      projectMetadata,
+      "confirmed"
+    );
+    expect(balance.amount.toNumber()).to.be.greaterThan(0);
+    expect(
+      after.referralFeeCounter.sub(before.referralFeeCounter).toString()
+    ).to.equal(balance.amount.toString());
+  });
+
+  it("Mint token referred by the signer fails", async () => {
+    await expectProgramError(
+      program.methods
+        .mintTokenWithSol({
+          amount: new anchor.BN(10000000),
+          symbol: "QP",
+          order: { exactIn: { minReceive: new anchor.BN(1) } },
+        })
+        .accountsStrict(referredMintAccounts(referrer.publicKey))
+        .signers([referrer])
+        .rpc(),
+      "SelfReferral"
+    );
+  });
+
+  it("Claim referral sol fees test passes", async () => {
+    const referralFee = (
+      await program.account.referralBalance.fetch(referral)
+    ).amount.toNumber();
+    const balanceBefore = await provider.connection.getBalance(
+      referrer.publicKey
+    );
+
+    await program.methods
+      .claimReferralSolFees({ symbol: "QP" })
+      .accountsStrict({
+        referral,
+        projectMetadata,
+        mint,
+        referrer: referrer.publicKey,
+        vault,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([referrer])
+      .rpc({ commitment: "confirmed" });
+
+    const balanceAfter = await provider.connection.getBalance(
+      referrer.publicKey,
+      "confirmed"
+    );
+    expect(balanceAfter - balanceBefore).to.equal(referralFee);
+    const balance = await program.account.referralBalance.fetch(
+      referral,
+      "confirmed"
+    );
+    expect(balance.amount.toNumber()).to.equal(0);
+    const metadata = await program.account.projectMetadata.fetch(
+      projectMetadata,
+      "confirmed"
+    );
+    expect(metadata.referralFeeCounter.toNumber()).to.equal(0);
+  });
+
+  // Same curve QP was created with, proposed again
+  const proposedCurve = {
+    exponential: {
+      a: new anchor.BN("100000000000000000"),
+      b: new anchor.BN("100000000000000000000000000"),
+    },
+  };
+  const proposeCurveAccounts = {
+    burveBase,
+    projectMetadata,
+    mint,
+    admin: payer.publicKey,
+  };
+  const setCurveAccounts = {
+    burveBase,
+    projectMetadata,
+    mint,
+    vault,
+    admin: payer.publicKey,
+    systemProgram: anchor.web3.SystemProgram.programId,
+  };
+
+  it("Propose project curve test passes", async () => {
+    const signature = await program.methods
+      .proposeProjectCurve({ bondingCurveType: proposedCurve })
+      .accountsStrict(proposeCurveAccounts)
+      .signers([payer])
+      .rpc({ commitment: "confirmed" });
+
+    const events = await emittedEvents(program, signature);
+    const proposed = events.find(
+      (event) => event.name === "projectCurveProposed"
+    );
+    const metadata = await program.account.projectMetadata.fetch(
+      projectMetadata,
+      "confirmed"
+    );
+    expect(metadata.pendingCurve.effectiveTime.toString()).to.equal(
+      proposed.data.effectiveTime.toString()
+    );
+    // Three days out
+    const now = Math.floor(Date.now() / 1000);
+    expect(metadata.pendingCurve.effectiveTime.toNumber()).to.be.greaterThan(
+      now + 3 * 24 * 60 * 60 - 60
+    );
+  });
+
+  it("Propose project curve by non admin fails", async () => {
+    await expectProgramError(
+      program.methods
+        .proposeProjectCurve({ bondingCurveType: proposedCurve })
+        .accountsStrict({ ...proposeCurveAccounts, admin: treasury.publicKey })
+        .signers([treasury])
+        .rpc(),
+      "SignerIsNotAdmin"
+    );
+  });
+
+  it("Set project curve before the timelock fails", async () => {
+    await expectProgramError(
+      program.methods
+        .setProjectCurveWithSol({ symbol: "QP" })
+        .accountsStrict(setCurveAccounts)
+        .signers([payer])
+        .rpc(),
+      "CurveTimelockActive"
+    );
+
+    // The proposal stays pending
+    const metadata = await program.account.projectMetadata.fetch(
+      projectMetadata
+    );
+    expect(metadata.pendingCurve).to.not.equal(null);
+  });
+
+  it("Set project treasury while a curve change is pending fails", async () => {
+    await expectProgramError(
+      program.methods
+        .setProjectTreasury(Keypair.generate().publicKey)
+        .accountsStrict({ projectMetadata, admin: payer.publicKey })
+        .signers([payer])
+        .rpc(),
+      "CurveChangePending"
+    );
+  });
+
+  // 70/30 split of the QP project fee
+  const splitRecipients = [Keypair.generate(), Keypair.generate()];
+  const split = [
+    { recipient: splitRecipients[0].publicKey, weight: 7000 },
+    { recipient: splitRecipients[1].publicKey, weight: 3000 },
+  ];
+  const splitAccounts = (recipients: PublicKey[]) =>
+    recipients.map((pubkey) => ({
+      pubkey,
+      isWritable: true,
+      isSigner: false,
+    }));
+  const distributeAccounts = {
+    projectMetadata,
+    mint,
+    vault,
+    systemProgram: anchor.web3.SystemProgram.programId,
+  };
+
+  it("Set revenue split to a wallet below rent fails", async () => {
+    await expectProgramError(
+      program.methods
+        .setProjectRevenueSplit(split)
+        .accountsStrict({ projectMetadata, admin: payer.publicKey })
+        .remainingAccounts(splitAccounts(split.map((s) => s.recipient)))
+        .signers([payer])
+        .rpc(),
+      "RecipientNotValid"
+    );
+  });
+
+  it("Airdrop revenue split recipients to keep them alive", async () => {
+    for (const recipient of splitRecipients) {
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(recipient.publicKey, 3000000),
+        "confirmed"
+      );
+    }
+  });
+
+  it("Set revenue split with weights not adding up fails", async () => {
+    await expectProgramError(
+      program.methods
+        .setProjectRevenueSplit([split[0], { ...split[1], weight: 2000 }])
+        .accountsStrict({ projectMetadata, admin: payer.publicKey })
+        .remainingAccounts(splitAccounts(split.map((s) => s.recipient)))
+        .signers([payer])
+        .rpc(),
+      "RevenueSplitNotValid"
+    );
+  });
+
+  it("Set revenue split with recipients out of order fails", async () => {
+    await expectProgramError(
+      program.methods
+        .setProjectRevenueSplit(split)
+        .accountsStrict({ projectMetadata, admin: payer.publicKey })
+        .remainingAccounts(
+          splitAccounts(split.map((s) => s.recipient).reverse())
+        )
+        .signers([payer])
+        .rpc(),
+      "RecipientNotValid"
+    );
+  });
+
+  it("Set revenue split by non admin fails", async () => {
+    await expectProgramError(
+      program.methods
+        .setProjectRevenueSplit(split)
+        .accountsStrict({ projectMetadata, admin: treasury.publicKey })
+        .remainingAccounts(splitAccounts(split.map((s) => s.recipient)))
+        .signers([treasury])
+        .rpc(),
+      "SignerIsNotAdmin"
+    );
+  });
+
+  it("Set revenue split with project fees pending test passes", async () => {
+    const before = await program.account.projectMetadata.fetch(
+      projectMetadata
+    );
+    expect(before.projectTaxCounter.toNumber()).to.be.greaterThan(0);
+
+    await program.methods
+      .setProjectRevenueSplit(split)
+      .accountsStrict({ projectMetadata, admin: payer.publicKey })
+      .remainingAccounts(splitAccounts(split.map((s) => s.recipient)))
+      .signers([payer])
+      .rpc();
+
+    const after = await program.account.projectMetadata.fetch(projectMetadata);
+    expect(after.revenueSplit.length).to.equal(2);
+    expect(after.projectTaxCounter.toString()).to.equal(
+      before.projectTaxCounter.toString()
+    );
+  });
+
+  it("Claim project sol tax with a revenue split fails", async () => {
+    await expectProgramError(
+      program.methods
+        .claimProjectSolTax({ symbol: "QP" })
+        .accountsStrict({
+          projectMetadata,
+          mint,
+          admin: payer.publicKey,
+          vault,
+          projectTreasury: treasury.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([payer])
+        .rpc(),
+      "ProjectTaxIsSplit"
+    );
+  });
+
+  it("Distribute project sol tax to other recipients fails", async () => {
+    await expectProgramError(
+      program.methods
+        .distributeProjectSolTax({ symbol: "QP" })
+        .accountsStrict(distributeAccounts)
+        .remainingAccounts(
+          splitAccounts([split[0].recipient, treasury.publicKey])
+        )
+        .rpc(),
+      "RecipientNotValid"
+    );
+  });
+
+  it("Distribute project sol tax test passes", async () => {
+    const projectTax = (
+      await program.account.projectMetadata.fetch(projectMetadata)
+    ).projectTaxCounter.toNumber();
+    const balancesBefore = await Promise.all(
+      split.map((s) => provider.connection.getBalance(s.recipient))
+    );
+
+    // Anyone can crank it
+    await program.methods
+      .distributeProjectSolTax({ symbol: "QP" })
+      .accountsStrict(distributeAccounts)
+      .remainingAccounts(splitAccounts(split.map((s) => s.recipient)))
+      .rpc({ commitment: "confirmed" });
+
+    const balancesAfter = await Promise.all(
+      split.map((s) => provider.connection.getBalance(s.recipient, "confirmed"))
+    );
+    const firstShare = Math.floor((projectTax * 7000) / 10000);
+    expect(balancesAfter[0] - balancesBefore[0]).to.equal(firstShare);
+    expect(balancesAfter[1] - balancesBefore[1]).to.equal(
+      projectTax - firstShare
+    );
+    const metadata = await program.account.projectMetadata.fetch(
+      projectMetadata,
+      "confirmed"
+    );
+    expect(metadata.projectTaxCounter.toNumber()).to.equal(0);
+  });
+
+  it("Clear revenue split test passes", async () => {
+    await program.methods
+      .setProjectRevenueSplit([])
+      .accountsStrict({ projectMetadata, admin: payer.publicKey })
+      .signers([payer])
+      .rpc();
+
+    const metadata = await program.account.projectMetadata.fetch(
+      projectMetadata
+    );
+    expect(metadata.revenueSplit).to.be.empty;
+  });
+
+  const claimProjectTaxAccounts = {
+    projectMetadata,
+    mint,
+    admin: payer.publicKey,
+    vault,
+    projectTreasury: treasury.publicKey,
+    systemProgram: anchor.web3.SystemProgram.programId,
+  };
+
+  it("Mint token accrues the project fee", async () => {
+    const before = await program.account.projectMetadata.fetch(
+      projectMetadata
+    );
+    await program.methods
+      .mintTokenWithSol({
+        amount: new anchor.BN(1000000),
+        symbol: "QP",
+        order: { exactIn: { minReceive: new anchor.BN(1) } },
+      })
+      .accountsStrict({
+        burveBase,
+        projectMetadata,
+        from: payer.publicKey,
+        mint: mint,
+        vault,
+        mintTokenAccount: associatedAddress({
+          mint: mint,
+          owner: payer.publicKey,
+        }),
+        referral: null,
         systemProgram: anchor.web3.SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
         tokenProgram: TOKEN_2022_PROGRAM_ID,
       })
       .signers([payer])
       .rpc();
+
+    // 0.5% of the payment
+    const after = await program.account.projectMetadata.fetch(projectMetadata);
+    expect(
+      after.projectTaxCounter.sub(before.projectTaxCounter).toNumber()
+    ).to.equal(5000);
+  });
+
+  it("Claim project sol tax by non admin fails", async () => {
+    await expectProgramError(
+      program.methods
+        .claimProjectSolTax({ symbol: "QP" })
+        .accountsStrict({
+          ...claimProjectTaxAccounts,
+          admin: treasury.publicKey,
+        })
+        .signers([treasury])
+        .rpc(),
+      "SignerIsNotAdmin"
+    );
+  });
+
+  it("Claim project sol tax test passes", async () => {
+    const projectTax = (
+      await program.account.projectMetadata.fetch(projectMetadata)
+    ).projectTaxCounter.toNumber();
+    const balanceBefore = await provider.connection.getBalance(
+      treasury.publicKey
+    );
+
+    await program.methods
+      .claimProjectSolTax({ symbol: "QP" })
+      .accountsStrict(claimProjectTaxAccounts)
+      .signers([payer])
+      .rpc({ commitment: "confirmed" });
+
+    const balanceAfter = await provider.connection.getBalance(
+      treasury.publicKey,
+      "confirmed"
+    );
+    expect(balanceAfter - balanceBefore).to.equal(projectTax);
+    const metadata = await program.account.projectMetadata.fetch(
+      projectMetadata,
+      "confirmed"
+    );
+    expect(metadata.projectTaxCounter.toNumber()).to.equal(0);
+  });
+
+  // Capped at 10 tokens
+  const maxSupply = new anchor.BN(10000000000);
+  const [capMint] = PublicKey.findProgramAddressSync(
+    [
+      anchor.utils.bytes.utf8.encode("token-mint-account"),
+      anchor.utils.bytes.utf8.encode("CAP"),
+    ],
+    program.programId
+  );
+  const [capVault] = PublicKey.findProgramAddressSync(
+    [anchor.utils.bytes.utf8.encode("vault"), capMint.toBuffer()],
+    program.programId
+  );
+  const [capProjectMetadata] = PublicKey.findProgramAddressSync(
+    [anchor.utils.bytes.utf8.encode("project-metadata"), capMint.toBuffer()],
+    program.programId
+  );
+  const capMintAccounts = {
+    burveBase,
+    projectMetadata: capProjectMetadata,
+    from: payer.publicKey,
+    mint: capMint,
+    vault: capVault,
+    mintTokenAccount: associatedAddress({
+      mint: capMint,
+      owner: payer.publicKey,
+    }),
+    referral: null,
+    systemProgram: anchor.web3.SystemProgram.programId,
+    associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+    tokenProgram: TOKEN_2022_PROGRAM_ID,
+  };
+
+  it("Create capped project test passes", async () => {
+    const bondingCurveType = {
+      exponential: {
+        a: new anchor.BN("100000000000000000"),
+        b: new anchor.BN("100000000000000000000000000"),
+      },
+    };
+    await program.methods
+      .createNewProjectWithSol({
+        name: "capped project",
+        symbol: "CAP",
+        uri: "https://my-project-data.com/metadata.json",
+        decimals: 9,
+        admin: payer.publicKey,
+        treasury: treasury.publicKey,
+        mintTax: 50,
+        burnTax: 50,
+        bondingCurveType,
+        maxSupply,
+        launchAuction: null,
+      })
+      .accountsStrict({
+        burveBase,
+        projectMetadata: capProjectMetadata,
+        payer: payer.publicKey,
+        vault: capVault,
+        mint: capMint,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([payer])
+      .rpc();
+  });
+
+  it("Mint exact token within max supply test passes", async () => {
+    await program.methods
+      .mintTokenWithSol({
+        amount: new anchor.BN(1000000000),
+        symbol: "CAP",
+        order: { exactOut: { maxPay: new anchor.BN(1000000000) } },
+      })
+      .accountsStrict(capMintAccounts)
+      .signers([payer])
+      .rpc();
+  });
+
+  it("Mint exact token past max supply fails", async () => {
+    await expectProgramError(
+      program.methods
+        .mintTokenWithSol({
+          amount: maxSupply,
+          symbol: "CAP",
+          order: { exactOut: { maxPay: new anchor.BN(5000000000) } },
+        })
+        .accountsStrict(capMintAccounts)
+        .signers([payer])
+        .rpc(),
+      "MaxSupplyReached"
+    );
+  });
+
+  it("Mint token past max supply fills up to it", async () => {
+    // Far more than the 9 tokens left cost
+    const amount = new anchor.BN(3000000000);
+    const balanceBefore = await provider.connection.getBalance(
+      payer.publicKey
+    );
+    const signature = await program.methods
+      .mintTokenWithSol({
+        amount,
+        symbol: "CAP",
+        order: { exactIn: { minReceive: new anchor.BN(1) } },
+      })
+      .accountsStrict(capMintAccounts)
+      .signers([payer])
+      .rpc({ commitment: "confirmed" });
+    const balanceAfter = await provider.connection.getBalance(
+      payer.publicKey,
+      "confirmed"
+    );
+
+    const minted = await provider.connection.getTokenAccountBalance(
+      capMintAccounts.mintTokenAccount,
+      "confirmed"
+    );
+    expect(minted.value.amount).to.equal(maxSupply.toString());
+    // Only the cost of the tokens minted leaves the buyer
+    const spent = balanceBefore - balanceAfter;
+    expect(spent).to.be.greaterThan(0);
+    expect(spent).to.be.lessThan(amount.toNumber() / 2);
+
+    const events = await emittedEvents(program, signature);
+    const capReached = events.find(
+      (event) => event.name === "supplyCapReached"
+    );
+    expect(capReached.data.mint.toBase58()).to.equal(capMint.toBase58());
+    expect(capReached.data.maxSupply.toString()).to.equal(
+      maxSupply.toString()
+    );
+  });
+
+  it("Mint token at max supply fails", async () => {
+    await expectProgramError(
+      program.methods
+        .mintTokenWithSol({
+          amount: new anchor.BN(1000000),
+          symbol: "CAP",
+          order: { exactIn: { minReceive: new anchor.BN(1) } },
+        })
+        .accountsStrict(capMintAccounts)
+        .signers([payer])
+        .rpc(),
+      "MaxSupplyReached"
+    );
+  });
+
+  it("Set project curve without a proposal fails", async () => {
+    await expectProgramError(
+      program.methods
+        .setProjectCurveWithSol({ symbol: "CAP" })
+        .accountsStrict({
+          burveBase,
+          projectMetadata: capProjectMetadata,
+          mint: capMint,
+          vault: capVault,
+          admin: payer.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([payer])
+        .rpc(),
+      "NoPendingCurve"
+    );
   });
 
   const newPayer = Keypair.generate();
@@ -200,16 +1032,16 @@ describe("burve-solana-contract", () => {
       .mintTokenWithSol({
         amount: new anchor.BN(1000000),
         symbol: "QP",
-        minReceive: new anchor.BN(100),
+        order: { exactIn: { minReceive: new anchor.BN(100) } },
       })
       .accountsStrict({
         burveBase,
         projectMetadata,
-        projectTreasury: treasury.publicKey,
         from: newPayer.publicKey,
         mint: mint,
         vault,
         mintTokenAccount: newTreasury,
+        referral: null,
         systemProgram: anchor.web3.SystemProgram.programId,
         associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
         tokenProgram: TOKEN_2022_PROGRAM_ID,
@@ -227,13 +1059,17 @@ describe("burve-solana-contract", () => {
         name: "new project with spl",
         symbol: "NPS",
         uri: "https://my-project-data.com/metadata.json",
+        decimals: 9,
         admin: newPayer.publicKey,
         treasury: newTreasury,
         mintTax: 50,
         burnTax: 50,
         bondingCurveType,
+        maxSupply: null,
+        launchAuction: null,
       })
       .accountsStrict({
+        burveBase,
         projectMetadata: newProjectMetadata,
         payer: newPayer.publicKey,
         vault: newVault,
@@ -260,12 +1096,11 @@ describe("burve-solana-contract", () => {
       .mintTokenWithSpl({
         amount: new anchor.BN(100),
         symbol: "NPS",
-        minReceive: new anchor.BN(100),
+        order: { exactIn: { minReceive: new anchor.BN(100) } },
       })
       .accountsStrict({
         burveBase,
         projectMetadata: newProjectMetadata,
-        projectTreasury: newTreasury,
         signer: newPayer.publicKey,
         raisingToken: mint,
         fromAta: associatedAddress({
@@ -279,6 +1114,7 @@ describe("burve-solana-contract", () => {
           owner: newPayer.publicKey,
         }),
 
+        referral: null,
         systemProgram: anchor.web3.SystemProgram.programId,
         associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
         tokenProgram: TOKEN_2022_PROGRAM_ID,
@@ -292,12 +1128,11 @@ describe("burve-solana-contract", () => {
       .burnTokenToSpl({
         amount: new anchor.BN(10),
         symbol: "NPS",
-        minReceive: new anchor.BN(10),
+        order: { exactIn: { minReceive: new anchor.BN(10) } },
       })
       .accountsStrict({
         burveBase,
         projectMetadata: newProjectMetadata,
-        projectTreasury: newTreasury,
         signer: newPayer.publicKey,
         raisingToken: mint,
         burnTokenAccount: associatedAddress({
@@ -310,6 +1145,7 @@ describe("burve-solana-contract", () => {
           mint: mint,
           owner: newPayer.publicKey,
         }),
+        referral: null,
         systemProgram: anchor.web3.SystemProgram.programId,
         tokenProgram: TOKEN_2022_PROGRAM_ID,
       })
@@ -317,6 +1153,47 @@ describe("burve-solana-contract", () => {
       .rpc();
   });
 
+  it("Set revenue split to a token account of another mint fails", async () => {
+    // The NPS token account, not one of the QP raising token
+    const recipient = associatedAddress({
+      mint: newMint,
+      owner: newPayer.publicKey,
+    });
+    await expectProgramError(
+      program.methods
+        .setProjectRevenueSplit([{ recipient, weight: 10000 }])
+        .accountsStrict({
+          projectMetadata: newProjectMetadata,
+          admin: newPayer.publicKey,
+        })
+        .remainingAccounts(splitAccounts([recipient]))
+        .signers([newPayer])
+        .rpc(),
+      "RecipientNotValid"
+    );
+  });
+
+  it("Set revenue split to a raising token account test passes", async () => {
+    await program.methods
+      .setProjectRevenueSplit([{ recipient: newTreasury, weight: 10000 }])
+      .accountsStrict({
+        projectMetadata: newProjectMetadata,
+        admin: newPayer.publicKey,
+      })
+      .remainingAccounts(splitAccounts([newTreasury]))
+      .signers([newPayer])
+      .rpc();
+
+    await program.methods
+      .setProjectRevenueSplit([])
+      .accountsStrict({
+        projectMetadata: newProjectMetadata,
+        admin: newPayer.publicKey,
+      })
+      .signers([newPayer])
+      .rpc();
+  });
+
   it("Claim burve sol tax test passes", async () => {
     await program.methods
       .claimBurveSolTax({
@@ -354,4 +1231,118 @@ describe("burve-solana-contract", () => {
       .signers([payer])
       .rpc();
   });
+
+  // Remaining accounts of a batch claim for one project
+  const batchProject = (
+    metadata: PublicKey,
+    projectVault: PublicKey,
+    projectMint: PublicKey
+  ) => [
+    { pubkey: metadata, isWritable: true, isSigner: false },
+    { pubkey: projectVault, isWritable: true, isSigner: false },
+    { pubkey: projectMint, isWritable: false, isSigner: false },
+  ];
+  const claimSolBatchAccounts = {
+    burveBase,
+    admin: payer.publicKey,
+    burveTreasury: payer.publicKey,
+    systemProgram: anchor.web3.SystemProgram.programId,
+  };
+
+  it("Claim burve sol tax batch with a project twice fails", async () => {
+    await expectProgramError(
+      program.methods
+        .claimBurveSolTaxBatch()
+        .accountsStrict(claimSolBatchAccounts)
+        .remainingAccounts([
+          ...batchProject(capProjectMetadata, capVault, capMint),
+          ...batchProject(capProjectMetadata, capVault, capMint),
+        ])
+        .signers([payer])
+        .rpc(),
+      "ProjectAccountsNotValid"
+    );
+  });
+
+  it("Claim burve sol tax batch with another project's vault fails", async () => {
+    await expectProgramError(
+      program.methods
+        .claimBurveSolTaxBatch()
+        .accountsStrict(claimSolBatchAccounts)
+        .remainingAccounts(batchProject(capProjectMetadata, vault, capMint))
+        .signers([payer])
+        .rpc(),
+      "ProjectAccountsNotValid"
+    );
+  });
+
+  it("Claim burve sol tax batch with a spl project fails", async () => {
+    await expectProgramError(
+      program.methods
+        .claimBurveSolTaxBatch()
+        .accountsStrict(claimSolBatchAccounts)
+        .remainingAccounts([
+          ...batchProject(capProjectMetadata, capVault, capMint),
+          ...batchProject(newProjectMetadata, newVault, newMint),
+        ])
+        .signers([payer])
+        .rpc(),
+      "ProjectAccountsNotValid"
+    );
+  });
+
+  it("Claim burve sol tax batch by non admin fails", async () => {
+    await expectProgramError(
+      program.methods
+        .claimBurveSolTaxBatch()
+        .accountsStrict({ ...claimSolBatchAccounts, admin: treasury.publicKey })
+        .remainingAccounts(batchProject(capProjectMetadata, capVault, capMint))
+        .signers([treasury])
+        .rpc(),
+      "SignerIsNotAdmin"
+    );
+  });
+
+  it("Claim burve sol tax batch test passes", async () => {
+    // QP was claimed above, so only CAP has tax to claim
+    const qpTax = (await program.account.projectMetadata.fetch(projectMetadata))
+      .burveTaxCounter;
+    expect(qpTax.toNumber()).to.equal(0);
+    const capTax = (
+      await program.account.projectMetadata.fetch(capProjectMetadata)
+    ).burveTaxCounter;
+    expect(capTax.toNumber()).to.be.greaterThan(0);
+    const balanceBefore = await provider.connection.getBalance(
+      payer.publicKey
+    );
+
+    const signature = await program.methods
+      .claimBurveSolTaxBatch()
+      .accountsStrict(claimSolBatchAccounts)
+      .remainingAccounts([
+        ...batchProject(projectMetadata, vault, mint),
+        ...batchProject(capProjectMetadata, capVault, capMint),
+      ])
+      .signers([payer])
+      .rpc({ commitment: "confirmed" });
+
+    const balanceAfter = await provider.connection.getBalance(
+      payer.publicKey,
+      "confirmed"
+    );
+    expect(balanceAfter - balanceBefore).to.equal(capTax.toNumber());
+
+    const claimed = (await emittedEvents(program, signature)).filter(
+      (event) => event.name === "burveTaxClaimed"
+    );
+    expect(claimed.length).to.equal(1);
+    expect(claimed[0].data.mint.toBase58()).to.equal(capMint.toBase58());
+    expect(claimed[0].data.amount.toString()).to.equal(capTax.toString());
+
+    const metadata = await program.account.projectMetadata.fetch(
+      capProjectMetadata,
+      "confirmed"
+    );
+    expect(metadata.burveTaxCounter.toNumber()).to.equal(0);
+  });
 });